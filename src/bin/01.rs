@@ -1,87 +1,92 @@
-use std::collections::HashMap;
-use std::usize;
-
-advent_of_code::solution!(1);
-
-pub fn part_one(input: &str) -> Option<usize> {
-    let mut total_calibration: usize = 0;
-    for line in input.lines() {
-        let extracted_digits = line
-            .chars()
-            .filter(|ch| ch.is_ascii_digit())
-            .collect::<String>();
-        let calibration = ((extracted_digits
-            .chars()
-            .next()
-            .unwrap()
-            .to_digit(10)
-            .unwrap()
-            * 10)
-            + extracted_digits
-                .chars()
-                .last()
-                .unwrap()
-                .to_digit(10)
-                .unwrap()) as usize;
-        total_calibration += calibration;
+use std::convert::Infallible;
+use std::str::FromStr;
+
+use advent_of_code::matcher::Matcher;
+use advent_of_code::template::options;
+
+advent_of_code::solution!(1, parse_document);
+
+const ENGLISH: [&str; 9] = [
+    "one", "two", "three", "four", "five", "six", "seven", "eight", "nine",
+];
+
+/// The words for the digits one to nine, chosen with `cargo solve 1 --words eins,zwei,...,neun`.
+struct Words(Vec<String>);
+
+impl FromStr for Words {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let words: Vec<String> = s.split(',').map(|word| word.trim().to_string()).collect();
+        if words.len() != 9 || words.iter().any(|word| word.is_empty()) {
+            return Err("expecting nine words for the digits one to nine".to_string());
+        }
+        Ok(Words(words))
     }
-    Some(total_calibration)
 }
 
-pub fn part_two(input: &str) -> Option<usize> {
-    let mut total_calibrations = 0;
-    let numbers_text: Vec<&str> = vec![
-        "one", "two", "three", "four", "five", "six", "seven", "eight", "nine",
-    ];
-    let numbers_val: HashMap<&str, usize> = HashMap::from([
-        ("one", 1),
-        ("two", 2),
-        ("three", 3),
-        ("four", 4),
-        ("five", 5),
-        ("six", 6),
-        ("seven", 7),
-        ("eight", 8),
-        ("nine", 9),
-    ]);
-    for line in input.lines() {
-        let mut first_digit: usize = 0;
-        let mut last_digit = 0;
-        'outer: for (idx, ch) in line.char_indices() {
-            if ch.is_numeric() {
-                first_digit = (ch.to_digit(10).unwrap_or_default() * 10) as usize;
-                break;
-            } else {
-                for number in numbers_text.iter().enumerate() {
-                    let starts_with = line[idx..line.len()].starts_with(number.1);
-                    if starts_with && numbers_val.contains_key(number.1) {
-                        let key_val = numbers_val.get_key_value(number.1);
-                        first_digit = key_val.unwrap().1 * 10;
-                        break 'outer;
-                    }
-                }
-            }
+/// A matcher for the digits `0` to `9` and for `words`, which stand for the digits from one on.
+fn vocabulary<W: AsRef<str>>(words: &[W]) -> Matcher<usize> {
+    let digits = (0..10).map(|digit| (digit.to_string(), digit));
+    let words = words
+        .iter()
+        .zip(1..)
+        .map(|(word, digit)| (word.as_ref().to_string(), digit));
+    Matcher::new(digits.chain(words))
+}
+
+/// The first and the last digit of a line as a two-digit number. Tokens may overlap, so `twone` ends with one.
+/// Returns `None` if the line has no digits.
+fn calibration_value(vocabulary: &Matcher<usize>, line: &str) -> Option<usize> {
+    let mut tokens = vocabulary.find_overlapping(line);
+    let mut first = tokens.next()?;
+    let mut last = first;
+    // tokens are reported by their end, a longer token may start before the one reported earlier.
+    for token in tokens {
+        if token.start < first.start {
+            first = token;
         }
-        'outer: for (idx, ch) in line.chars().rev().collect::<String>().char_indices() {
-            if ch.is_numeric() {
-                last_digit = (ch.to_digit(10).unwrap_or_default()) as usize;
-                break;
-            } else {
-                for number in numbers_text.iter().enumerate() {
-                    let reversed = number.1.chars().rev().collect::<String>();
-                    if line.chars().rev().collect::<String>()[idx..line.len()]
-                        .starts_with(&reversed)
-                    {
-                        last_digit = numbers_val[number.1];
-                        break 'outer;
-                    }
-                }
-            }
+        if token.start > last.start {
+            last = token;
         }
-        total_calibrations += first_digit + last_digit;
-        //println!("Calibration for {} is {}", line, (first_digit + last_digit));
     }
-    Some(total_calibrations)
+    Some(first.value * 10 + last.value)
+}
+
+/// The calibration document with the vocabulary of part one, which is built once.
+pub struct Document<'a> {
+    input: &'a str,
+    digits: Matcher<usize>,
+}
+
+fn parse_document(input: &str) -> Result<Document<'_>, Infallible> {
+    Ok(Document {
+        input,
+        digits: vocabulary::<&str>(&[]),
+    })
+}
+
+/// The words from the command line, the English words if none are given
+fn words() -> Vec<String> {
+    match options::value::<Words>("--words") {
+        Some(Words(words)) => words,
+        None => ENGLISH.map(String::from).to_vec(),
+    }
+}
+
+fn calibration_sum(input: &str, vocabulary: &Matcher<usize>) -> Option<usize> {
+    input
+        .lines()
+        .map(|line| calibration_value(vocabulary, line))
+        .sum()
+}
+
+pub fn part_one(document: &Document) -> Option<usize> {
+    calibration_sum(document.input, &document.digits)
+}
+
+pub fn part_two(document: &Document) -> Option<usize> {
+    calibration_sum(document.input, &vocabulary(&words()))
 }
 
 #[cfg(test)]
@@ -90,13 +95,34 @@ mod tests {
 
     #[test]
     fn test_part_one() {
-        let result = part_one(&advent_of_code::template::read_file("examples", DAY));
+        let input = advent_of_code::template::read_file("examples", DAY);
+        let result = part_one(&parse_document(&input).unwrap());
         assert_eq!(result, Some(209));
     }
 
     #[test]
     fn test_part_two() {
-        let result = part_two(&advent_of_code::template::read_file("examples", DAY));
+        let input = advent_of_code::template::read_file("examples", DAY);
+        let result = part_two(&parse_document(&input).unwrap());
         assert_eq!(result, Some(198));
     }
+
+    #[test]
+    fn test_vocabularies() {
+        let english = vocabulary(&ENGLISH);
+        assert_eq!(calibration_value(&english, "twone"), Some(21));
+        assert_eq!(calibration_value(&english, "eightwothree"), Some(83));
+        assert_eq!(calibration_value(&english, "0nine"), Some(9));
+        assert_eq!(calibration_value(&english, "abc"), None);
+        assert_eq!(part_one(&parse_document("abc\n1").unwrap()), None);
+
+        let Ok(Words(german)) = "eins,zwei,drei,vier,fünf,sechs,sieben,acht,neun".parse() else {
+            panic!("nine words are valid");
+        };
+        let german = vocabulary(&german);
+        assert_eq!(calibration_value(&german, "xzweinsx"), Some(21));
+        assert_eq!(calibration_value(&german, "fünfzehn7acht"), Some(58));
+        assert_eq!(calibration_value(&german, "one"), None);
+        assert!("eins,zwei".parse::<Words>().is_err());
+    }
 }
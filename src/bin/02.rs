@@ -1,132 +1,196 @@
-use std::cmp::max;
-use std::collections::HashMap;
+use std::collections::BTreeMap;
+use std::str::FromStr;
 
-use regex::Regex;
+use advent_of_code::parse::{self, ParseError};
+use advent_of_code::template::options;
 
-advent_of_code::solution!(2);
+advent_of_code::solution!(2, parse_games);
 
-#[derive(Debug)]
-struct Game {
-    id: usize,
-    //(R, G, B)
-    cubes_drawn: Vec<(usize, usize, usize)>,
-}
+/// The cubes of one handful, counted by colour. Colours that are not mentioned count as zero.
+#[derive(Debug, PartialEq, Eq, Clone, Default)]
+pub struct Draw(BTreeMap<String, usize>);
 
-impl Game {
-    fn new_game(id: usize) -> Game {
-        Game {
-            id,
-            cubes_drawn: Vec::new(),
+/// The contents of a bag, written like a draw, e.g. `12 red, 13 green, 14 blue`.
+/// Chosen with `cargo solve 2 --bag "<count> <colour>, ..."`.
+type Bag = Draw;
+
+impl Draw {
+    /// The bag of the puzzle: 12 red, 13 green and 14 blue cubes
+    fn default_bag() -> Bag {
+        let mut bag = BTreeMap::new();
+        for (colour, count) in [("red", 12), ("green", 13), ("blue", 14)] {
+            bag.insert(colour.to_string(), count);
         }
+        Draw(bag)
+    }
+
+    fn count(&self, colour: &str) -> usize {
+        self.0.get(colour).copied().unwrap_or_default()
+    }
+
+    fn colours(&self) -> impl Iterator<Item = &str> {
+        self.0.keys().map(String::as_str)
     }
-    fn insert_subset(&mut self, red: usize, blue: usize, green: usize) {
-        self.cubes_drawn.push((red, green, blue));
+
+    /// Whether every cube of the draw could have come from `bag`
+    fn fits_in(&self, bag: &Bag) -> bool {
+        self.0
+            .iter()
+            .all(|(colour, &count)| count <= bag.count(colour))
+    }
+
+    /// The product of the counts of `colours`, zero if one of them is missing.
+    fn power<'a>(&self, colours: impl IntoIterator<Item = &'a str>) -> usize {
+        colours
+            .into_iter()
+            .map(|colour| self.count(colour))
+            .product()
     }
 }
-pub fn part_one(input: &str) -> Option<usize> {
-    let mut sum: usize = 0;
-    //(R, G, B)
-    let max_allowed: (usize, usize, usize) = (12, 13, 14);
-    for line in input.lines() {
-        let game = parse_line(line);
-        let mut is_game_valid = true;
-        for game_subset in game.cubes_drawn {
-            if game_subset.0.gt(&max_allowed.0)
-                || game_subset.1.gt(&max_allowed.1)
-                || game_subset.2.gt(&max_allowed.2)
-            {
-                is_game_valid = false;
-                break;
-            }
-        }
-        if is_game_valid {
-            sum += game.id;
+
+/// Reads a draw like `3 blue, 4 red`. A colour that is named twice counts twice.
+impl FromStr for Draw {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut cubes = BTreeMap::new();
+        for cube in s.split(',') {
+            let (count, colour) = match cube.split_whitespace().collect::<Vec<_>>()[..] {
+                [count, colour] => (count, colour),
+                _ => return Err(ParseError::at(s, cube.trim(), "a count and a colour")),
+            };
+            let count: usize = parse::parse_as(count).map_err(|err| err.located_in(s, count))?;
+            *cubes.entry(colour.to_string()).or_default() += count;
         }
+        Ok(Draw(cubes))
     }
-    Some(sum)
 }
 
-pub fn part_two(input: &str) -> Option<usize> {
-    let mut sum: usize = 0;
-    //(R, G, B)
-    for line in input.lines() {
-        let game = parse_line(line);
-        let mut min_red_cubes: usize = usize::MIN;
-        let mut min_green_cubes: usize = usize::MIN;
-        let mut min_blue_cubes: usize = usize::MIN;
-        for game_subset in game.cubes_drawn {
-            min_red_cubes = max(min_red_cubes, game_subset.0);
-            min_green_cubes = max(min_green_cubes, game_subset.1);
-            min_blue_cubes = max(min_blue_cubes, game_subset.2);
+#[derive(Debug)]
+pub struct Game {
+    id: usize,
+    draws: Vec<Draw>,
+}
+
+impl Game {
+    /// Whether every draw of the game could have come from `bag`
+    fn is_possible_with(&self, bag: &Bag) -> bool {
+        self.draws.iter().all(|draw| draw.fits_in(bag))
+    }
+
+    /// The bag with the fewest cubes of each colour that makes the game possible
+    fn minimal_bag(&self) -> Bag {
+        let mut bag = BTreeMap::new();
+        for (colour, &count) in self.draws.iter().flat_map(|draw| &draw.0) {
+            let least: &mut usize = bag.entry(colour.clone()).or_default();
+            *least = (*least).max(count);
         }
-        sum += min_red_cubes * min_green_cubes * min_blue_cubes;
-        //println!("min red: {}, green: {}, blue: {}, power: {}", min_red_cubes, min_green_cubes, min_blue_cubes, min_red_cubes*min_green_cubes*min_blue_cubes);
+        Draw(bag)
     }
-    Some(sum)
 }
 
-fn parse_line(line: &str) -> Game {
-    let game_id: &str = line
-        .trim()
-        .split(':')
-        .next()
-        .unwrap()
-        .split(' ')
-        .last()
-        .unwrap();
-    let cubes = line
-        .trim_start()
-        .split(':')
-        .last()
-        .unwrap()
-        .split(';')
-        .collect::<Vec<&str>>();
-    //println!("game: {}", game_id);
-    let re = Regex::new(r"(\d+) (red|green|blue)").unwrap();
-
-    let mut game = Game::new_game(game_id.parse().unwrap());
-    for cube in cubes {
-        let mut red_cubes: usize = 0;
-        let mut blue_cubes: usize = 0;
-        let mut green_cubes: usize = 0;
-        let mut color_count: HashMap<String, usize> = HashMap::new();
-        for capture in re.captures_iter(cube) {
-            let number: usize = capture[1].parse().unwrap();
-            let color = capture[2].to_string();
-            *color_count.entry(color).or_insert(0) += number;
-        }
-        for color in color_count {
-            match color.0.as_str() {
-                "green" => {
-                    green_cubes += color.1;
-                }
-                "red" => {
-                    red_cubes += color.1;
-                }
-                "blue" => {
-                    blue_cubes += color.1;
-                }
-                _ => {}
-            }
-        }
-        game.insert_subset(red_cubes, blue_cubes, green_cubes);
+/// Reads a game like `Game 1: 3 blue, 4 red; 1 red, 2 green`.
+impl FromStr for Game {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (game, draws) = parse::key_value(s)?;
+        let id = parse::expect_prefix(game, "Game ").map_err(|err| err.located_in(s, game))?;
+        let id = parse::parse_as(id).map_err(|err| err.located_in(s, id))?;
+        let draws = draws
+            .split(';')
+            .map(|draw| {
+                draw.parse()
+                    .map_err(|err: ParseError| err.located_in(s, draw))
+            })
+            .collect::<Result<_, _>>()?;
+        Ok(Game { id, draws })
     }
-    //println!("game: {:?}", game);
-    game
 }
+
+fn parse_games(input: &str) -> Result<Vec<Game>, ParseError> {
+    parse::lines_with(input, str::parse)
+}
+
+/// The bag from the command line, the bag of the puzzle if none is given
+fn bag() -> Bag {
+    options::value("--bag").unwrap_or_else(Draw::default_bag)
+}
+
+fn sum_of_possible_ids(games: &[Game], bag: &Bag) -> usize {
+    games
+        .iter()
+        .filter(|game| game.is_possible_with(bag))
+        .map(|game| game.id)
+        .sum()
+}
+
+/// The colours of `bag` decide which counts are multiplied, so a game without blue cubes has power zero.
+fn sum_of_powers(games: &[Game], bag: &Bag) -> usize {
+    games
+        .iter()
+        .map(|game| game.minimal_bag().power(bag.colours()))
+        .sum()
+}
+
+pub fn part_one(games: &[Game]) -> Option<usize> {
+    Some(sum_of_possible_ids(games, &bag()))
+}
+
+pub fn part_two(games: &[Game]) -> Option<usize> {
+    Some(sum_of_powers(games, &bag()))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
     #[test]
     fn test_part_one() {
-        let result = part_one(&advent_of_code::template::read_file("examples", DAY));
+        let input = advent_of_code::template::read_file("examples", DAY);
+        let result = part_one(&parse_games(&input).unwrap());
         assert_eq!(result, Some(8));
     }
 
     #[test]
     fn test_part_two() {
-        let result = part_two(&advent_of_code::template::read_file("examples", DAY));
+        let input = advent_of_code::template::read_file("examples", DAY);
+        let result = part_two(&parse_games(&input).unwrap());
         assert_eq!(result, Some(2286));
     }
+
+    #[test]
+    fn test_game_model() {
+        let game: Game = "Game 3: 8 green, 6 blue, 20 red; 5 blue, 4 red, 13 green; 5 green, 1 red"
+            .parse()
+            .unwrap();
+        let minimal = game.minimal_bag();
+        assert_eq!(minimal, "20 red, 13 green, 6 blue".parse().unwrap());
+        assert_eq!(minimal.power(["red", "green", "blue"]), 1560);
+        assert!(!game.is_possible_with(&Draw::default_bag()));
+        assert_eq!(
+            Draw::default_bag(),
+            "12 red, 13 green, 14 blue".parse().unwrap()
+        );
+        assert!(game.is_possible_with(&minimal));
+
+        // other colours, and a colour that is named twice in one draw.
+        let game: Game = "Game 7: 2 yellow, 1 yellow; 1 red".parse().unwrap();
+        assert_eq!(game.draws[0].count("yellow"), 3);
+        assert!(game.is_possible_with(&"3 yellow, 1 red".parse().unwrap()));
+        assert!(!game.is_possible_with(&"3 yellow".parse().unwrap()));
+
+        let bag: Bag = "1 red, 1 blue".parse().unwrap();
+        assert_eq!(sum_of_powers(&[game], &bag), 0);
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        let error = parse_games("Game 1: 3 blue\nGame 2: 3 blue, red").unwrap_err();
+        assert_eq!((error.line, error.column), (2, 17));
+        let error = parse_games("Game x: 3 blue").unwrap_err();
+        assert_eq!((error.line, error.column), (1, 6));
+        assert!(parse_games("Round 1: 3 blue").is_err());
+        assert!("12 red 13 green".parse::<Bag>().is_err());
+    }
 }
@@ -1,109 +1,138 @@
-use regex::Regex;
+use advent_of_code::grid::{Grid, GridError};
+use advent_of_code::parse::ParseError;
+use advent_of_code::point::Point;
 
-use advent_of_code::point::{Point, DIAGONALS};
+advent_of_code::solution!(3, parse_engine_schematic);
 
-advent_of_code::solution!(3);
+/// A number of the schematic, written over `len` cells from `col` to the right.
+struct Span {
+    value: u32,
+    row: usize,
+    col: usize,
+    len: usize,
+}
 
 pub struct Schematic {
-    gears: Vec<(char, Point)>,
-    symbols: Vec<(char, Point)>,
-    numbers: Vec<(String, Point)>,
+    cells: Grid<char>,
+    spans: Vec<Span>,
+    /// The index of the span that covers each cell, if any
+    span_ids: Grid<Option<usize>>,
 }
 
-fn parse_engine_schematic(input: &str) -> Option<Schematic> {
-    let mut numbers: Vec<(String, Point)> = vec![];
-    let num_re = Regex::new(r"\d+").unwrap();
-    for (row, line) in input.lines().enumerate() {
-        for num_match in num_re.find_iter(line) {
-            numbers.push((
-                num_match.as_str().parse().unwrap(),
-                Point::new(row as i32, num_match.start() as i32),
-            ));
-        }
+/// The point of the cell at `row` and `col`. Points use `x` for the column and `y` for the row.
+fn cell(row: usize, col: usize) -> Point {
+    Point::new(col as i32, row as i32)
+}
+
+fn is_symbol(c: char) -> bool {
+    !c.is_ascii_digit() && c != '.'
+}
+
+impl Schematic {
+    /// The symbols in the cells around `span`
+    fn symbols_adjacent_to<'a>(&'a self, span: &Span) -> impl Iterator<Item = char> + 'a {
+        let rows = span.row.saturating_sub(1)..=span.row + 1;
+        let cols = span.col.saturating_sub(1)..=span.col + span.len;
+
+        rows.flat_map(move |row| cols.clone().map(move |col| cell(row, col)))
+            .filter_map(|point| self.cells.get(point).copied())
+            .filter(|&c| is_symbol(c))
     }
-    let mut symbols: Vec<(char, Point)> = vec![];
-    for (row, line) in input.lines().enumerate() {
-        for (col, ch) in line.chars().enumerate() {
-            if !ch.is_ascii_digit() && ch != '.' {
-                symbols.push((ch, Point::new(row as i32, col as i32)));
-            }
-        }
+
+    /// The numbers in the cells around `point`, each number once
+    fn numbers_adjacent_to(&self, point: Point) -> Vec<&Span> {
+        let mut ids: Vec<usize> = self
+            .cells
+            .neighbors8(point)
+            .filter_map(|neighbor| self.span_ids[neighbor])
+            .collect();
+        ids.sort_unstable();
+        ids.dedup();
+        ids.into_iter().map(|id| &self.spans[id]).collect()
     }
-    let mut gears: Vec<(char, Point)> = vec![];
-    for (row, line) in input.lines().enumerate() {
-        for (col, ch) in line.chars().enumerate() {
-            if ch == '*' {
-                gears.push((ch, Point::new(row as i32, col as i32)));
+}
+
+fn parse_engine_schematic(input: &str) -> Result<Schematic, ParseError> {
+    let cells: Grid<char> = input.parse().map_err(|err| match err {
+        GridError::RaggedRow { line, expected, .. } => ParseError::at(
+            input,
+            input.lines().nth(line - 1).unwrap_or(input),
+            format!("a row of {expected} cells"),
+        ),
+        GridError::InvalidCell { line, column, cell } => ParseError {
+            line,
+            column,
+            text: cell.to_string(),
+            expected: "a valid cell".to_string(),
+        },
+    })?;
+
+    let mut spans = vec![];
+    let mut span_ids = Grid::new(cells.width(), cells.height(), None);
+    for (row, line) in cells.rows().enumerate() {
+        let mut col = 0;
+        while col < line.len() {
+            let len = line[col..]
+                .iter()
+                .take_while(|c| c.is_ascii_digit())
+                .count();
+            if len == 0 {
+                col += 1;
+                continue;
+            }
+
+            let digits = &line[col..col + len];
+            let value = digits
+                .iter()
+                .try_fold(0_u32, |value, c| {
+                    value.checked_mul(10)?.checked_add(c.to_digit(10)?)
+                })
+                .ok_or_else(|| ParseError {
+                    line: row + 1,
+                    column: col + 1,
+                    text: digits.iter().collect(),
+                    expected: "a number that fits `u32`".to_string(),
+                })?;
+
+            for offset in 0..len {
+                span_ids[cell(row, col + offset)] = Some(spans.len());
             }
+            spans.push(Span {
+                value,
+                row,
+                col,
+                len,
+            });
+            col += len;
         }
     }
-    Some(Schematic {
-        gears,
-        symbols,
-        numbers,
+
+    Ok(Schematic {
+        cells,
+        spans,
+        span_ids,
     })
 }
 
-pub fn part_one(input: &str) -> Option<usize> {
-    let Schematic {
-        numbers, symbols, ..
-    } = parse_engine_schematic(input).unwrap();
-    let mut part_numbers: usize = 0;
-    for (number, starting_point) in numbers {
-        let num_end_pos = Point::new(
-            starting_point.x,
-            starting_point.y + number.to_string().len() as i32 - 1,
-        );
-        for (_, symbol_pos) in symbols.iter() {
-            let neighbors = DIAGONALS
-                .into_iter()
-                .map(|diagonal| diagonal + *symbol_pos)
-                .collect::<Vec<Point>>();
-            for neighbor in neighbors.iter() {
-                if neighbor.x == starting_point.x
-                    && neighbor.y >= starting_point.y
-                    && neighbor.y <= num_end_pos.y
-                {
-                    part_numbers += number.parse::<usize>().unwrap_or_default();
-                    break;
-                }
-            }
-        }
-    }
+pub fn part_one(schematic: &Schematic) -> Option<usize> {
+    let part_numbers = schematic
+        .spans
+        .iter()
+        .filter(|span| schematic.symbols_adjacent_to(span).next().is_some())
+        .map(|span| span.value as usize)
+        .sum();
     Some(part_numbers)
 }
 
-pub fn part_two(input: &str) -> Option<u32> {
-    let Schematic {
-        numbers,
-        symbols: _symbols,
-        gears,
-    } = parse_engine_schematic(input).unwrap();
-    let mut gear_ratios: Vec<u32> = vec![];
-    for (_, gear_pos) in gears.iter() {
-        let neighbors = DIAGONALS
-            .into_iter()
-            .map(|diagonal| diagonal + *gear_pos)
-            .collect::<Vec<Point>>();
-        let mut adjacent_nums: Vec<u32> = vec![];
-        for (num_str, num_start_pos) in numbers.iter() {
-            let num_end_pos =
-                Point::new(num_start_pos.x, num_start_pos.y + num_str.len() as i32 - 1);
-            for neighbor in neighbors.iter() {
-                if neighbor.x == num_start_pos.x
-                    && neighbor.y >= num_start_pos.y
-                    && neighbor.y <= num_end_pos.y
-                {
-                    adjacent_nums.push(num_str.parse::<u32>().unwrap());
-                    break;
-                }
-            }
-        }
-        if adjacent_nums.len() == 2 {
-            gear_ratios.push(adjacent_nums.iter().product());
-        }
-    }
-    Some(gear_ratios.iter().sum())
+pub fn part_two(schematic: &Schematic) -> Option<u32> {
+    let gear_ratios = schematic
+        .cells
+        .find_all(&'*')
+        .map(|gear| schematic.numbers_adjacent_to(gear))
+        .filter(|numbers| numbers.len() == 2)
+        .map(|numbers| numbers.iter().map(|span| span.value).product::<u32>())
+        .sum();
+    Some(gear_ratios)
 }
 
 #[cfg(test)]
@@ -112,13 +141,41 @@ mod tests {
 
     #[test]
     fn test_part_one() {
-        let result = part_one(&advent_of_code::template::read_file("examples", DAY));
+        let input = advent_of_code::template::read_file("examples", DAY);
+        let result = part_one(&parse_engine_schematic(&input).unwrap());
         assert_eq!(result, Some(4361));
     }
 
     #[test]
     fn test_part_two() {
-        let result = part_two(&advent_of_code::template::read_file("examples", DAY));
+        let input = advent_of_code::template::read_file("examples", DAY);
+        let result = part_two(&parse_engine_schematic(&input).unwrap());
         assert_eq!(result, Some(467835));
     }
+
+    #[test]
+    fn test_spans() {
+        let schematic = parse_engine_schematic("12.\n..*\n3.4").unwrap();
+        let values: Vec<(u32, usize, usize)> = schematic
+            .spans
+            .iter()
+            .map(|span| (span.value, span.row, span.col))
+            .collect();
+        assert_eq!(values, [(12, 0, 0), (3, 2, 0), (4, 2, 2)]);
+
+        // the gear in row 1 and column 2 touches 12 and 4, but not 3.
+        let numbers = schematic.numbers_adjacent_to(cell(1, 2));
+        let values: Vec<u32> = numbers.iter().map(|span| span.value).collect();
+        assert_eq!(values, [12, 4]);
+        assert_eq!(part_one(&schematic), Some(16));
+
+        // a number next to two symbols is still only one part number.
+        let schematic = parse_engine_schematic("#5#").unwrap();
+        assert_eq!(part_one(&schematic), Some(5));
+
+        let error = parse_engine_schematic("..\n...").err().unwrap();
+        assert_eq!(error.line, 2);
+        let error = parse_engine_schematic(".99999999999").err().unwrap();
+        assert_eq!((error.line, error.column), (1, 2));
+    }
 }
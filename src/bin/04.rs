@@ -1,100 +1,71 @@
 use std::str::FromStr;
 
-use regex::Regex;
+use advent_of_code::parse::{self, ParseError};
 
-advent_of_code::solution!(4);
+advent_of_code::solution!(4, parse_cards);
 
 #[derive(Clone)]
-struct ParsedCard {
-    card_number: u32,
+pub struct ParsedCard {
     winning_numbers: Vec<u32>,
     current_numbers: Vec<u32>,
 }
 
 impl FromStr for ParsedCard {
-    type Err = &'static str;
+    type Err = ParseError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let parts: Vec<&str> = s.split(|c| c == ':' || c == '|').collect();
-
-        if parts.len() != 3 {
-            return Err("Invalid input format");
-        }
-
-        let re = Regex::new(r"Card (\d+)").unwrap();
-        let card_number: u32 = if let Some(captures) = re.captures(parts[0]) {
-            if let Some(number_str) = captures.get(1) {
-                number_str.as_str().parse::<u32>().unwrap_or_default()
-            } else {
-                0
-            }
-        } else {
-            0
-        };
-        let numbers_before_pipe: Vec<u32> = parts[1]
-            .split_whitespace()
-            .map(|num| num.parse().map_err(|_| "Invalid number in the first list"))
-            .collect::<Result<_, _>>()?;
-        let numbers_after_pipe: Vec<u32> = parts[2]
-            .split_whitespace()
-            .map(|num| num.parse().map_err(|_| "Invalid number in the second list"))
-            .collect::<Result<_, _>>()?;
+        let (card, numbers) = parse::key_value(s)?;
+        let (winning, current) = numbers
+            .split_once('|')
+            .ok_or_else(|| ParseError::at_end(s, "\"|\""))?;
+
+        let card_number = parse::expect_prefix(card, "Card")?.trim();
+        parse::parse_as::<u32>(card_number).map_err(|err| err.located_in(s, card_number))?;
+        let numbers_before_pipe: Vec<u32> =
+            parse::words_of(winning).map_err(|err| err.located_in(s, winning))?;
+        let numbers_after_pipe: Vec<u32> =
+            parse::words_of(current).map_err(|err| err.located_in(s, current))?;
 
         Ok(ParsedCard {
-            card_number,
             winning_numbers: numbers_before_pipe,
             current_numbers: numbers_after_pipe,
         })
     }
 }
 
-pub fn part_one(input: &str) -> Option<u32> {
+fn parse_cards(input: &str) -> Result<Vec<ParsedCard>, ParseError> {
+    parse::lines_with(input, str::parse)
+}
+
+pub fn part_one(cards: &[ParsedCard]) -> Option<u32> {
     let mut points: u32 = 0;
-    for line in input.lines() {
+    for parsed_card in cards {
         let mut current_card_point: u32 = 0;
-        match line.parse::<ParsedCard>() {
-            Ok(parsed_card) => {
-                println!("Card Number: {}", parsed_card.card_number);
-                println!("Numbers before pipe: {:?}", parsed_card.winning_numbers);
-                println!("Numbers after pipe: {:?}", parsed_card.current_numbers);
-                let winning_numbers: Vec<u32> = parsed_card
-                    .winning_numbers
-                    .iter()
-                    .filter(|number| parsed_card.current_numbers.contains(number))
-                    .cloned()
-                    .collect();
-                points += if winning_numbers.len() == 1 {
-                    //current_card_point = 1;
-                    1
-                } else if !winning_numbers.is_empty() {
-                    for number in 0..winning_numbers.len() {
-                        if number == 0 {
-                            current_card_point = 1;
-                        } else {
-                            current_card_point *= 2;
-                        }
-                    }
-                    current_card_point
+        let winning_numbers: Vec<u32> = parsed_card
+            .winning_numbers
+            .iter()
+            .filter(|number| parsed_card.current_numbers.contains(number))
+            .cloned()
+            .collect();
+        points += if winning_numbers.len() == 1 {
+            1
+        } else if !winning_numbers.is_empty() {
+            for number in 0..winning_numbers.len() {
+                if number == 0 {
+                    current_card_point = 1;
                 } else {
-                    0
-                };
-
-                println!("points: {points}");
-            }
-            Err(err) => {
-                eprintln!("Error parsing input: {}", err);
+                    current_card_point *= 2;
+                }
             }
-        }
+            current_card_point
+        } else {
+            0
+        };
     }
     Some(points)
 }
 
-pub fn part_two(input: &str) -> Option<u32> {
-    let mut cards = Vec::new();
-    for line in input.lines() {
-        let cc = line.parse::<ParsedCard>().unwrap();
-        cards.push(cc.clone());
-    }
+pub fn part_two(cards: &[ParsedCard]) -> Option<u32> {
     let mut cards_count = vec![1; cards.len()];
 
     for (idx, card) in cards.iter().enumerate() {
@@ -119,13 +90,26 @@ mod tests {
 
     #[test]
     fn test_part_one() {
-        let result = part_one(&advent_of_code::template::read_file("examples", DAY));
+        let input = advent_of_code::template::read_file("examples", DAY);
+        let result = part_one(&parse_cards(&input).unwrap());
         assert_eq!(result, Some(13));
     }
 
     #[test]
     fn test_part_two() {
-        let result = part_two(&advent_of_code::template::read_file("examples", DAY));
+        let input = advent_of_code::template::read_file("examples", DAY);
+        let result = part_two(&parse_cards(&input).unwrap());
         assert_eq!(result, Some(30));
     }
+
+    #[test]
+    fn test_parse_error() {
+        let input = "Card 1: 41 48 | 83 86\nCard 2: 13 x2 | 61 30";
+        let error = parse_cards(input).err().unwrap();
+        assert_eq!((error.line, error.column), (2, 12));
+        assert_eq!(error.text, "x2");
+
+        let error = parse_cards("Card 1: 41 48").err().unwrap();
+        assert_eq!(error.expected, "\"|\"");
+    }
 }
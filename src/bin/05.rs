@@ -1,199 +1,212 @@
-advent_of_code::solution!(5);
-
-type SeedSoilMapping = Vec<(isize, isize, isize)>;
-type SoilFertilizerMapping = Vec<(isize, isize, isize)>;
-type FertilizerWaterMapping = Vec<(isize, isize, isize)>;
-type WaterLightMapping = Vec<(isize, isize, isize)>;
-type LightTempMapping = Vec<(isize, isize, isize)>;
-type TempHumidityMapping = Vec<(isize, isize, isize)>;
-type HumidityLocationMapping = Vec<(isize, isize, isize)>;
-
-fn parse_mapping(lines: &mut dyn Iterator<Item = &str>) -> Vec<(isize, isize, isize)> {
-    lines
-        .skip(1) // Skip the header line
-        .take_while(|&line| !line.trim().is_empty()) // Stop parsing when an empty line is encountered
-        .filter_map(|line| {
-            let values: Vec<isize> = line
-                .split_whitespace()
-                .map(|s| s.parse().unwrap_or_default())
-                .collect();
-            if values.len() == 3 {
-                Some((values[0], values[1], values[2]))
-            } else {
-                None
-            }
-        })
-        .collect()
-}
+use std::str::FromStr;
+
+use advent_of_code::parse::{self, ParseError};
+use advent_of_code::ranges::{RangeMap, RangeSet};
+use advent_of_code::template::options;
 
-fn parse_input(
-    input: &str,
-) -> (
-    Vec<isize>,
-    SeedSoilMapping,
-    SoilFertilizerMapping,
-    FertilizerWaterMapping,
-    WaterLightMapping,
-    LightTempMapping,
-    TempHumidityMapping,
-    HumidityLocationMapping,
-) {
-    let mut lines = input.lines();
-
-    // Parse the first line
-    let seeds: Vec<isize> = lines
-        .next()
-        .unwrap()
-        .split_whitespace()
-        .skip(1)
-        .map(|s| s.parse().unwrap())
-        .collect();
-
-    // Parse seed-to-soil map
-    let seed_to_soil = parse_mapping(&mut lines);
-
-    // Parse soil-to-fertilizer map
-    let soil_to_fertilizer = parse_mapping(&mut lines);
-
-    // Parse fertilizer-to-water map
-    let fertilizer_to_water = parse_mapping(&mut lines);
-
-    // Parse water-to-light map
-    let water_to_light = parse_mapping(&mut lines);
-
-    // Parse light-to-temperature map
-    let light_to_temp = parse_mapping(&mut lines);
-
-    // Parse temperature-to-humidity map
-    let temp_to_humidity = parse_mapping(&mut lines);
-
-    // Parse humidity-to-location map
-    let humidity_to_location = parse_mapping(&mut lines);
-
-    (
-        seeds,
-        seed_to_soil,
-        soil_to_fertilizer,
-        fertilizer_to_water,
-        water_to_light,
-        light_to_temp,
-        temp_to_humidity,
-        humidity_to_location,
-    )
+advent_of_code::solution!(5, parse_input);
+
+/// How part two is solved, chosen with `cargo solve 5 --strategy <forward|reverse>`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+enum Strategy {
+    /// Maps all seed ranges through the composed almanac at once.
+    #[default]
+    Forward,
+    /// Tries every location from 0 upwards until one comes from a seed.
+    Reverse,
 }
 
-fn get_category_dst(category_src: &isize, mapping: &[(isize, isize, isize)]) -> isize {
-    let mut category_dst: isize = -1;
-    for ss_mapping in mapping.iter() {
-        if ss_mapping.1 <= *category_src && ss_mapping.1 + ss_mapping.2 >= *category_src {
-            category_dst = ss_mapping.0 + (category_src - ss_mapping.1);
-            break;
+impl FromStr for Strategy {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "forward" => Ok(Strategy::Forward),
+            "reverse" => Ok(Strategy::Reverse),
+            _ => Err("expecting `forward` or `reverse`".to_string()),
         }
     }
-    if category_dst == -1 {
-        category_dst = *category_src;
+}
+
+/// One section of the almanac, e.g. `seed-to-soil map:`
+#[derive(Debug)]
+pub struct Mapping {
+    source: String,
+    destination: String,
+    map: RangeMap,
+}
+
+impl Mapping {
+    /// The destination number of a source number
+    fn get(&self, value: i64) -> i64 {
+        self.map.get(value)
+    }
+
+    /// All source numbers that correspond to a destination number, in no particular order
+    fn preimage(&self, value: i64) -> impl Iterator<Item = i64> + '_ {
+        self.map.preimages(value)
     }
-    //print!("{}: {} ", category_dst_str, category_dst);
-    category_dst
 }
 
-pub fn part_one(input: &str) -> Option<u32> {
-    let (
-        seeds,
-        seed_to_soil,
-        soil_to_fertilizer,
-        fertilizer_to_water,
-        water_to_light,
-        light_to_temp,
-        temp_to_humidity,
-        humidity_to_location,
-    ) = parse_input(input);
-
-    let mut locations: Vec<isize> = Vec::new();
-    get_min_location(
-        seeds,
-        &seed_to_soil,
-        &soil_to_fertilizer,
-        &fertilizer_to_water,
-        &water_to_light,
-        &light_to_temp,
-        &temp_to_humidity,
-        &humidity_to_location,
-        &mut locations,
-    );
-    Some(*locations.iter().min().unwrap() as u32)
+/// The seeds and the mappings from seeds to locations, in the order in which they are applied.
+#[derive(Debug)]
+pub struct Almanac {
+    seeds: Vec<i64>,
+    stages: Vec<Mapping>,
 }
 
-#[allow(clippy::too_many_arguments)]
-fn get_min_location(
-    seeds: Vec<isize>,
-    seed_to_soil: &SeedSoilMapping,
-    soil_to_fertilizer: &SoilFertilizerMapping,
-    fertilizer_to_water: &FertilizerWaterMapping,
-    water_to_light: &WaterLightMapping,
-    light_to_temp: &LightTempMapping,
-    temp_to_humidity: &TempHumidityMapping,
-    humidity_to_location: &HumidityLocationMapping,
-    locations: &mut Vec<isize>,
-) {
-    for seed in seeds.iter() {
-        //print!("seed: {} ", *seed);
-        let soil = get_category_dst(seed, seed_to_soil);
-        let fertlizer = get_category_dst(&soil, soil_to_fertilizer);
-        let water = get_category_dst(&fertlizer, fertilizer_to_water);
-        let light = get_category_dst(&water, water_to_light);
-        let temp = get_category_dst(&light, light_to_temp);
-        let humidity = get_category_dst(&temp, temp_to_humidity);
-        let location = get_category_dst(&humidity, humidity_to_location);
-        locations.push(location);
-        //println!()
+impl Almanac {
+    /// Applies every stage to a seed.
+    fn location(&self, seed: i64) -> i64 {
+        self.stages
+            .iter()
+            .fold(seed, |value, stage| stage.get(value))
     }
+
+    /// Whether a seed that ends up at `location` matches `is_seed`.
+    /// Walks the preimages stage by stage, depth first, so nothing is collected.
+    fn has_seed_for(&self, location: i64, is_seed: impl Fn(i64) -> bool) -> bool {
+        fn search(stages: &[Mapping], value: i64, is_seed: &dyn Fn(i64) -> bool) -> bool {
+            match stages.split_last() {
+                Some((stage, earlier)) => stage
+                    .preimage(value)
+                    .any(|source| search(earlier, source, is_seed)),
+                None => is_seed(value),
+            }
+        }
+        search(&self.stages, location, &is_seed)
+    }
+
+    /// All stages combined into a single map from seeds to locations.
+    fn composed(&self) -> RangeMap {
+        advent_of_code::profile_scope!("compose");
+
+        self.stages
+            .iter()
+            .fold(RangeMap::new(), |map, stage| map.then(&stage.map))
+    }
+}
+
+fn parse_mapping(input: &str, paragraph: &str) -> Result<Mapping, ParseError> {
+    advent_of_code::profile_scope!("parse_mapping");
+
+    let (header, entries) = paragraph
+        .split_once('\n')
+        .unwrap_or((paragraph, &paragraph[paragraph.len()..]));
+    let (source, destination) = header
+        .trim_end()
+        .strip_suffix(" map:")
+        .and_then(|name| name.split_once("-to-"))
+        .ok_or_else(|| ParseError::at(input, header, "a map header like `seed-to-soil map:`"))?;
+
+    let mut map = RangeMap::new();
+    for line in entries.lines() {
+        let (source, destination) =
+            match parse::words_of(line).map_err(|err| err.located_in(input, line))?[..] {
+                [destination, source, len] => (source..source + len, destination),
+                _ => return Err(ParseError::at(input, line, "three numbers")),
+            };
+        if !map.insert(source, destination) {
+            return Err(ParseError::at(
+                input,
+                line,
+                "a range that does not overlap the others",
+            ));
+        }
+    }
+
+    Ok(Mapping {
+        source: source.to_string(),
+        destination: destination.to_string(),
+        map,
+    })
 }
 
-fn expand_pairs(input: Vec<isize>) -> Vec<isize> {
-    let mut result: Vec<isize> = Vec::new();
+fn parse_input(input: &str) -> Result<Almanac, ParseError> {
+    let mut paragraphs = parse::paragraphs(input);
 
-    // Iterate over pairs of start and range values
-    for i in (0..input.len()).step_by(2) {
-        if i + 1 < input.len() {
-            let start = input[i];
-            let range = input[i + 1];
+    let seeds_line = paragraphs.next().unwrap_or(input);
+    let seeds = parse::expect_prefix(seeds_line, "seeds:")
+        .map_err(|err| err.located_in(input, seeds_line))?;
+    let numbers: Vec<i64> = parse::words_of(seeds).map_err(|err| err.located_in(input, seeds))?;
+    if !numbers.len().is_multiple_of(2) {
+        let last = seeds.split_whitespace().last().unwrap_or(seeds_line);
+        return Err(ParseError::at(
+            input,
+            last,
+            "pairs of a range start and a length",
+        ));
+    }
 
-            // Generate the sequence of values and add them to the result vector
-            result.extend((start..start + range).collect::<Vec<isize>>());
+    let mut stages: Vec<Mapping> = vec![];
+    for paragraph in paragraphs {
+        let stage = parse_mapping(input, paragraph)?;
+        let expected = stages.last().map_or("seed", |stage| &stage.destination);
+        if stage.source != expected {
+            return Err(ParseError::at(
+                input,
+                paragraph.lines().next().unwrap_or(paragraph),
+                format!("a map from `{expected}`"),
+            ));
         }
+        stages.push(stage);
+    }
+
+    if stages
+        .last()
+        .is_none_or(|stage| stage.destination != "location")
+    {
+        return Err(ParseError::at_end(input, "a map to `location`"));
     }
 
-    result
+    Ok(Almanac {
+        seeds: numbers,
+        stages,
+    })
 }
 
-pub fn part_two(input: &str) -> Option<u32> {
-    let (
-        seeds,
-        seed_to_soil,
-        soil_to_fertilizer,
-        fertilizer_to_water,
-        water_to_light,
-        light_to_temp,
-        temp_to_humidity,
-        humidity_to_location,
-    ) = parse_input(input);
-
-    let updated_seeds_list = expand_pairs(seeds);
-    //println!("inc in seeds:{}", updated_seeds_list.len() - seeds.len());
-    let mut locations: Vec<isize> = Vec::new();
-    get_min_location(
-        updated_seeds_list,
-        &seed_to_soil,
-        &soil_to_fertilizer,
-        &fertilizer_to_water,
-        &water_to_light,
-        &light_to_temp,
-        &temp_to_humidity,
-        &humidity_to_location,
-        &mut locations,
-    );
-    Some(*locations.iter().min().unwrap() as u32)
+pub fn part_one(almanac: &Almanac) -> Option<u32> {
+    advent_of_code::profile_scope!("lowest_location");
+
+    almanac
+        .seeds
+        .iter()
+        .map(|&seed| almanac.location(seed))
+        .min()
+        .map(|location| location as u32)
+}
+
+/// Reads the seed numbers as pairs of range start and length. The parser checks that the count is even.
+fn seed_ranges(seeds: &[i64]) -> RangeSet {
+    seeds
+        .chunks_exact(2)
+        .map(|pair| pair[0]..pair[0] + pair[1])
+        .collect()
+}
+
+pub fn part_two(almanac: &Almanac) -> Option<u32> {
+    let seed_ranges = seed_ranges(&almanac.seeds);
+
+    let location = match options::value("--strategy").unwrap_or_default() {
+        Strategy::Forward => lowest_location_forward(almanac, &seed_ranges),
+        Strategy::Reverse => lowest_location_reverse(almanac, &seed_ranges),
+    };
+
+    location.map(|location| location as u32)
+}
+
+fn lowest_location_forward(almanac: &Almanac, seed_ranges: &RangeSet) -> Option<i64> {
+    let map = almanac.composed();
+    advent_of_code::profile_scope!("map_ranges");
+    map.map_set(seed_ranges).min()
+}
+
+fn lowest_location_reverse(almanac: &Almanac, seed_ranges: &RangeSet) -> Option<i64> {
+    advent_of_code::profile_scope!("search_locations");
+
+    // the location of any seed bounds the search.
+    let bound = almanac.location(seed_ranges.min()?);
+    (0..=bound).find(|&location| almanac.has_seed_for(location, |seed| seed_ranges.contains(seed)))
 }
 
 #[cfg(test)]
@@ -202,13 +215,77 @@ mod tests {
 
     #[test]
     fn test_part_one() {
-        let result = part_one(&advent_of_code::template::read_file("examples", DAY));
+        let input = advent_of_code::template::read_file("examples", DAY);
+        let result = part_one(&parse_input(&input).unwrap());
         assert_eq!(result, Some(35));
     }
 
     #[test]
     fn test_part_two() {
-        let result = part_two(&advent_of_code::template::read_file("examples", DAY));
+        let input = advent_of_code::template::read_file("examples", DAY);
+        let result = part_two(&parse_input(&input).unwrap());
         assert_eq!(result, Some(46));
     }
+
+    #[test]
+    fn test_strategies_agree() {
+        let input = advent_of_code::template::read_file("examples", DAY);
+        let almanac = parse_input(&input).unwrap();
+        for seeds in [&[79, 14, 55, 13][..], &[0, 1], &[90, 10, 3, 1], &[]] {
+            let seed_ranges = seed_ranges(seeds);
+            assert_eq!(
+                lowest_location_reverse(&almanac, &seed_ranges),
+                lowest_location_forward(&almanac, &seed_ranges)
+            );
+        }
+        assert_eq!("reverse".parse(), Ok(Strategy::Reverse));
+        assert!("backward".parse::<Strategy>().is_err());
+    }
+
+    #[test]
+    fn test_stages() {
+        let input = advent_of_code::template::read_file("examples", DAY);
+        let almanac = parse_input(&input).unwrap();
+        assert_eq!(almanac.stages.len(), 7);
+        assert_eq!(almanac.stages[1].source, "soil");
+
+        let composed = almanac.composed();
+        for seed in 0..110 {
+            let location = almanac.location(seed);
+            assert_eq!(composed.get(seed), location);
+            assert!(almanac.has_seed_for(location, |source| source == seed));
+        }
+        // 79 is the only seed that ends up at 82.
+        assert!(almanac.has_seed_for(82, |seed| seed == 79));
+        assert!(!almanac.has_seed_for(82, |seed| seed != 79));
+
+        let input = "seeds: 1 2\n\nseed-to-location map:\n5 0 3\n";
+        let almanac = parse_input(input).unwrap();
+        assert_eq!(part_one(&almanac), Some(6));
+    }
+
+    #[test]
+    fn test_truncated_input() {
+        let input = advent_of_code::template::read_file("examples", DAY);
+        let truncated = &input[..input.find("light-to-temperature").unwrap()];
+        let error = parse_input(truncated).unwrap_err();
+        assert_eq!(error.expected, "a map to `location`");
+        assert!(error.text.is_empty());
+
+        let input = input.replacen("50 98 2", "50 98", 1);
+        let error = parse_input(&input).unwrap_err();
+        assert_eq!((error.line, error.column), (4, 1));
+
+        let error = parse_input("seeds: 1 2\n\nsoil-to-location map:\n1 2 3").unwrap_err();
+        assert_eq!(
+            (error.line, error.expected.as_str()),
+            (3, "a map from `seed`")
+        );
+
+        let error = parse_input("seeds: 1 2 33\n\nseed-to-location map:\n1 2 3").unwrap_err();
+        assert_eq!(
+            (error.line, error.column, error.text.as_str()),
+            (1, 12, "33")
+        );
+    }
 }
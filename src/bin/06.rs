@@ -1,68 +1,127 @@
-advent_of_code::solution!(6);
-
-fn get_race_data(input: &str) -> Vec<(usize, usize)> {
-    let distances = input
-        .lines()
-        .last()
-        .unwrap()
-        .split_whitespace()
-        .skip(1)
-        .map(|s| s.parse().unwrap());
-    let times = input
-        .lines()
-        .next()
-        .unwrap()
-        .split_whitespace()
-        .skip(1)
-        .map(|s| s.parse().unwrap());
-    let race_data: Vec<(usize, usize)> = times.into_iter().zip(distances).collect();
-    race_data
+use std::str::FromStr;
+
+use advent_of_code::math;
+use advent_of_code::parse::{self, ParseError};
+use advent_of_code::template::options;
+
+advent_of_code::solution!(6, get_race_data);
+
+/// The time and the record distance of every race
+#[derive(Debug)]
+pub struct Races {
+    races: Vec<(usize, usize)>,
 }
 
-fn find_min_time_hold(time: usize, max_distance: usize) -> usize {
-    let mut range_start = 0;
-    let mut start: usize = 0;
-    let mut end: usize = time;
-
-    while start <= end {
-        let speed = (start + end) / 2;
-        let remaining_time = time - speed;
-        let total_distance = speed * remaining_time;
-        if total_distance > max_distance {
-            range_start = speed;
-            end = speed - 1;
-        } else {
-            start = speed + 1;
+fn get_race_data(input: &str) -> Result<Races, ParseError> {
+    let mut lines = input.lines();
+    let mut row = |name: &str| -> Result<Vec<usize>, ParseError> {
+        let line = lines
+            .next()
+            .ok_or_else(|| ParseError::at_end(input, format!("a `{name}:` line")))?;
+        let values = parse::expect_prefix(line, name)
+            .and_then(|rest| parse::expect_prefix(rest, ":"))
+            .map_err(|err| err.located_in(input, line))?;
+        parse::words_of(values).map_err(|err| err.located_in(input, values))
+    };
+
+    let times = row("Time")?;
+    let distances = row("Distance")?;
+    if times.len() != distances.len() {
+        let line = input.lines().nth(1).unwrap_or(input);
+        return Err(ParseError::at(
+            input,
+            line,
+            format!("{} distances, one per race", times.len()),
+        ));
+    }
+
+    Ok(Races {
+        races: times.into_iter().zip(distances).collect(),
+    })
+}
+
+/// How the winning hold times of a race are counted, chosen with `cargo solve 6 --strategy <closed-form|binary-search>`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+enum Strategy {
+    /// Solves `hold * (time - hold) > distance` for `hold` with an integer square root.
+    #[default]
+    ClosedForm,
+    /// Searches for the shortest winning hold time.
+    BinarySearch,
+}
+
+impl FromStr for Strategy {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "closed-form" => Ok(Strategy::ClosedForm),
+            "binary-search" => Ok(Strategy::BinarySearch),
+            _ => Err("expecting `closed-form` or `binary-search`".to_string()),
         }
     }
-    range_start
 }
 
-fn find_max_time_hold(time: usize, max_distance: usize) -> usize {
-    let mut range_end = 0;
-    let mut start: usize = 0;
-    let mut end: usize = time;
-
-    while start <= end {
-        let speed = (start + end) / 2;
-        let remaining_time = time - speed;
-        let total_distance = speed * remaining_time;
-        if total_distance > max_distance {
-            range_end = speed;
-            start = speed + 1;
+/// The strategy from the command line, the closed form if none is given
+fn strategy() -> Strategy {
+    options::value("--strategy").unwrap_or_default()
+}
+
+fn beats_record(hold: u128, time: u128, max_distance: u128) -> bool {
+    hold * (time - hold) > max_distance
+}
+
+/// The shortest winning hold time, found by binary search over the first half of the race.
+fn find_min_time_hold(time: u128, max_distance: u128) -> Option<u128> {
+    // the distance grows with the hold time until half of the race.
+    let (mut start, mut end) = (0, time / 2 + 1);
+    if !beats_record(end - 1, time, max_distance) {
+        return None;
+    }
+
+    while start < end {
+        let speed = start + (end - start) / 2;
+        if beats_record(speed, time, max_distance) {
+            end = speed;
         } else {
-            end = speed - 1;
+            start = speed + 1;
         }
     }
-    range_end
+    Some(start)
 }
 
-pub fn part_one(input: &str) -> Option<usize> {
-    let race_data = get_race_data(input);
+/// The shortest winning hold time, the smaller root of `hold^2 - time * hold + max_distance`.
+fn find_min_time_hold_closed_form(time: u128, max_distance: u128) -> Option<u128> {
+    let discriminant = (time * time).checked_sub(4 * max_distance)?;
+    let mut hold = (time - math::isqrt(discriminant)) / 2;
+
+    // the integer square root is rounded down, so the root may be off by one in either direction.
+    while hold > 0 && beats_record(hold - 1, time, max_distance) {
+        hold -= 1;
+    }
+    while hold <= time / 2 && !beats_record(hold, time, max_distance) {
+        hold += 1;
+    }
+    (hold <= time / 2).then_some(hold)
+}
+
+/// The number of hold times that beat the record. Winning hold times are symmetric around half of the race.
+fn count_ways(time: usize, max_distance: usize, strategy: Strategy) -> usize {
+    let (time, max_distance) = (time as u128, max_distance as u128);
+    let min_hold = match strategy {
+        Strategy::ClosedForm => find_min_time_hold_closed_form(time, max_distance),
+        Strategy::BinarySearch => find_min_time_hold(time, max_distance),
+    };
+    min_hold.map_or(0, |hold| (time - 2 * hold + 1) as usize)
+}
+
+pub fn part_one(race_data: &Races) -> Option<usize> {
+    let strategy = strategy();
     let mut result: usize = 1;
     let ways_possible: Vec<usize> = race_data
+        .races
         .iter()
-        .map(|race| find_max_time_hold(race.0, race.1) - find_min_time_hold(race.0, race.1) + 1)
+        .map(|race| count_ways(race.0, race.1, strategy))
         .collect();
     for possible_way in ways_possible {
         result *= possible_way;
@@ -70,31 +129,85 @@ pub fn part_one(input: &str) -> Option<usize> {
     Some(result)
 }
 
-pub fn part_two(input: &str) -> Option<usize> {
-    let race_data = get_race_data(input);
-    let combined_time_str: String = race_data.iter().map(|race| race.0.to_string()).collect();
-    let combined_time = combined_time_str.parse::<usize>().unwrap();
-    let combined_distance_str: String = race_data.iter().map(|race| race.1.to_string()).collect();
-    let combined_distance = combined_distance_str.parse::<usize>().unwrap();
-    let ways = find_max_time_hold(combined_time, combined_distance)
-        - find_min_time_hold(combined_time, combined_distance)
-        + 1;
+pub fn part_two(race_data: &Races) -> Option<usize> {
+    let races = &race_data.races;
+    let combined_time_str: String = races.iter().map(|race| race.0.to_string()).collect();
+    let combined_time = combined_time_str.parse::<usize>().ok()?;
+    let combined_distance_str: String = races.iter().map(|race| race.1.to_string()).collect();
+    let combined_distance = combined_distance_str.parse::<usize>().ok()?;
+    let ways = count_ways(combined_time, combined_distance, strategy());
     Some(ways)
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use advent_of_code::testing::Rng;
 
     #[test]
     fn test_part_one() {
-        let result = part_one(&advent_of_code::template::read_file("examples", DAY));
+        let input = advent_of_code::template::read_file("examples", DAY);
+        let result = part_one(&get_race_data(&input).unwrap());
         assert_eq!(result, Some(288));
     }
 
     #[test]
     fn test_part_two() {
-        let result = part_two(&advent_of_code::template::read_file("examples", DAY));
+        let input = advent_of_code::template::read_file("examples", DAY);
+        let result = part_two(&get_race_data(&input).unwrap());
         assert_eq!(result, Some(71503));
     }
+
+    #[test]
+    fn test_parse_error() {
+        let error = get_race_data("Time: 7 15\nDistance: 9").unwrap_err();
+        assert_eq!(error.line, 2);
+        assert_eq!(get_race_data("Time: 7 1x").unwrap_err().column, 9);
+    }
+
+    #[test]
+    fn test_strategies_match_brute_force() {
+        let mut rng = Rng::new(0xbb67_ae85_84ca_a73b);
+        for _ in 0..2000 {
+            let time = rng.below(100) as usize;
+            let max_distance = rng.below((time * time / 4 + 3) as u64) as usize;
+            let expected = (0..=time)
+                .filter(|hold| hold * (time - hold) > max_distance)
+                .count();
+
+            assert_eq!(
+                count_ways(time, max_distance, Strategy::ClosedForm),
+                expected
+            );
+            assert_eq!(
+                count_ways(time, max_distance, Strategy::BinarySearch),
+                expected
+            );
+        }
+    }
+
+    #[test]
+    fn test_strategies_agree_on_large_races() {
+        let mut rng = Rng::new(0x0123_4567_89ab_cdef);
+        for _ in 0..2000 {
+            let time = (rng.next_u64() >> 16) as usize;
+            let peak = (time as u128 * time as u128 / 4) as u64;
+            let max_distance = match rng.below(3) {
+                0 => rng.below(peak.max(1)),
+                1 => peak.saturating_sub(rng.below(4)),
+                _ => peak + rng.below(4),
+            } as usize;
+
+            assert_eq!(
+                count_ways(time, max_distance, Strategy::ClosedForm),
+                count_ways(time, max_distance, Strategy::BinarySearch)
+            );
+        }
+
+        assert_eq!(count_ways(0, 0, Strategy::BinarySearch), 0);
+        assert_eq!(count_ways(1, 0, Strategy::BinarySearch), 0);
+        assert_eq!(count_ways(2, 0, Strategy::ClosedForm), 1);
+        assert_eq!(count_ways(30, 1000, Strategy::BinarySearch), 0);
+        assert_eq!("binary-search".parse(), Ok(Strategy::BinarySearch));
+    }
 }
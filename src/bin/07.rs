@@ -1,173 +1,239 @@
-use std::char;
-use std::cmp::Ordering;
 use std::collections::HashMap;
 
-use crate::Rank::{FiveOfAKind, FourOfAKind, FullHouse, HighCard, OnePair, ThreeOfAKind, TwoPair};
+use advent_of_code::parse::{self, ParseError};
 
-advent_of_code::solution!(7);
+advent_of_code::solution!(7, parse_input);
 
+/// The type of a hand, from weakest to strongest.
 #[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Clone, Copy)]
 enum Rank {
-    FiveOfAKind(u8),
-    FourOfAKind(u8),
-    FullHouse(u8),
-    ThreeOfAKind(u8),
-    TwoPair(u8),
-    OnePair(u8),
-    HighCard(u8),
+    HighCard,
+    OnePair,
+    TwoPair,
+    ThreeOfAKind,
+    FullHouse,
+    FourOfAKind,
+    FiveOfAKind,
 }
 
+impl Rank {
+    /// The rank of a hand whose groups of equal cards have the given sizes, largest first.
+    fn from_counts(counts: &[usize]) -> Rank {
+        match counts {
+            [5, ..] => Rank::FiveOfAKind,
+            [4, ..] => Rank::FourOfAKind,
+            [3, 2, ..] => Rank::FullHouse,
+            [3, ..] => Rank::ThreeOfAKind,
+            [2, 2, ..] => Rank::TwoPair,
+            [2, ..] => Rank::OnePair,
+            _ => Rank::HighCard,
+        }
+    }
+}
+
+/// A card, ordered by its strength under the [`Rules`] it was read with.
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Clone, Copy)]
+struct Card(usize);
+
+/// A hand compares by its rank first and then card by card.
 #[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Clone)]
 struct Hand {
-    cards: Vec<char>,
     rank: Rank,
+    cards: Vec<Card>,
 }
 
-impl Hand {
-    fn new(cards: Vec<char>, rank: Rank) -> Self {
-        Hand { cards, rank }
-    }
+/// How hands are evaluated: the strength of every card and the cards that are wild.
+struct Rules {
+    /// The strength of each card by its byte, from 1 for the weakest card, 0 for bytes that are not cards
+    strengths: [u8; 256],
+    wildcards: [bool; 256],
 }
 
-type Hands = (Hand, usize);
+const STANDARD_RULES: Rules = Rules::new("AKQJT98765432", "");
 
-fn rank(cards: Vec<char>) -> Rank {
-    let mut freq_map: HashMap<char, usize> = HashMap::new();
-    for &card in &cards {
-        *freq_map.entry(card).or_insert(0) += 1;
-    }
-    let max_freq = *freq_map.values().max().unwrap();
-
-    match max_freq {
-        5 => FiveOfAKind(0),
-        4 => FourOfAKind(1),
-        3 => {
-            if freq_map.len() == 2 {
-                FullHouse(2)
-            } else {
-                ThreeOfAKind(3)
-            }
+const JOKER_RULES: Rules = Rules::new("AKQT98765432J", "J");
+
+impl Rules {
+    /// Rules for an ASCII card alphabet `order`, from strongest to weakest card, and the cards that are wild.
+    const fn new(order: &str, wildcards: &str) -> Rules {
+        let (order, wildcards) = (order.as_bytes(), wildcards.as_bytes());
+        let mut rules = Rules {
+            strengths: [0; 256],
+            wildcards: [false; 256],
+        };
+
+        let mut idx = 0;
+        while idx < order.len() {
+            rules.strengths[order[idx] as usize] = (order.len() - idx) as u8;
+            idx += 1;
         }
-        2 => {
-            let pairs = freq_map.iter().filter(|&(_, &freq)| freq == 2).count();
-            if pairs == 2 {
-                TwoPair(4)
-            } else {
-                OnePair(5)
-            }
+        let mut idx = 0;
+        while idx < wildcards.len() {
+            rules.wildcards[wildcards[idx] as usize] = true;
+            idx += 1;
         }
-        _ => HighCard(6),
+        rules
     }
-}
 
-fn rank_part_two(cards: Vec<char>) -> Rank {
-    let current_rank = rank(cards.clone());
-    let mut freq_map: HashMap<char, usize> = HashMap::new();
-    for &card in &cards {
-        *freq_map.entry(card).or_insert(0) += 1;
-    }
-    let freq_j = freq_map.entry('J').or_default();
-    if *freq_j == 0 {
-        return current_rank;
+    fn is_card(&self, card: char) -> bool {
+        card.is_ascii() && self.strengths[card as usize] > 0
     }
-    match current_rank {
-        FiveOfAKind(0) => FiveOfAKind(0),
-        FourOfAKind(1) => FiveOfAKind(0),
-        FullHouse(2) => FiveOfAKind(0),
-        ThreeOfAKind(3) => FourOfAKind(1),
-        TwoPair(4) => {
-            if *freq_j == 2 {
-                FourOfAKind(1)
-            } else {
-                FullHouse(2)
-            }
-        }
-        OnePair(5) => ThreeOfAKind(3),
-        HighCard(6) => OnePair(5),
-        _ => panic!("Invalid current rank: {:?}", current_rank),
+
+    /// Evaluates a hand. Returns `None` if a card is not part of the alphabet.
+    fn hand(&self, cards: &str) -> Option<Hand> {
+        let mut wildcards = 0;
+        let mut counts: HashMap<u8, usize> = HashMap::new();
+        let cards = cards
+            .bytes()
+            .map(|card| {
+                let strength = self.strengths[card as usize];
+                if strength == 0 {
+                    return None;
+                }
+                if self.wildcards[card as usize] {
+                    wildcards += 1;
+                } else {
+                    *counts.entry(card).or_default() += 1;
+                }
+                Some(Card(strength as usize))
+            })
+            .collect::<Option<Vec<Card>>>()?;
+
+        Some(Hand {
+            rank: Rank::from_counts(&promote(counts.into_values().collect(), wildcards)),
+            cards,
+        })
     }
 }
 
-fn compare_cards(a: char, b: char, cards_order: &str) -> Ordering {
-    let rank_a = cards_order.find(a).unwrap();
-    let rank_b = cards_order.find(b).unwrap();
-
-    rank_a.cmp(&rank_b)
+/// Sorts the group sizes of a hand, largest first, and lets the wildcards join the largest group.
+/// Ranks only get better with larger groups, so no other choice of wildcards leads to a better rank.
+fn promote(mut counts: Vec<usize>, wildcards: usize) -> Vec<usize> {
+    counts.sort_unstable_by(|a, b| b.cmp(a));
+    match counts.first_mut() {
+        Some(largest) => *largest += wildcards,
+        None if wildcards > 0 => counts.push(wildcards),
+        None => {}
+    }
+    counts
 }
 
-fn cmp_hands(a: &Hand, b: &Hand, cards_ordering: &str) -> Ordering {
-    match a.rank.cmp(&b.rank) {
-        Ordering::Less => Ordering::Less,
-        Ordering::Greater => Ordering::Greater,
-        Ordering::Equal => {
-            let ordering: Vec<Ordering> = a
-                .cards
-                .iter()
-                .zip(b.cards.iter())
-                .skip_while(|hand| hand.0 == hand.1)
-                .map(|hand| compare_cards(*hand.0, *hand.1, cards_ordering))
-                .collect();
-            *ordering.first().unwrap()
+/// Reads the hands and their bids. Both rules know the same cards, so every hand can be evaluated with either.
+fn parse_input(input: &str) -> Result<Vec<(&str, usize)>, ParseError> {
+    parse::lines_with(input, |line| {
+        let [cards, bid] = line.split_whitespace().collect::<Vec<_>>()[..] else {
+            return Err(ParseError::at(line, line, "cards and a bid"));
+        };
+        if let Some((idx, card)) = cards
+            .char_indices()
+            .find(|&(_, card)| !STANDARD_RULES.is_card(card))
+        {
+            let card = &cards[idx..idx + card.len_utf8()];
+            return Err(ParseError::at(line, card, "a card of `AKQJT98765432`"));
         }
-    }
+        let bid = parse::parse_as(bid).map_err(|err| err.located_in(line, bid))?;
+        Ok((cards, bid))
+    })
 }
 
-fn parse_input(input: &str, ranking_fn: fn(Vec<char>) -> Rank) -> Vec<Hands> {
-    let hands: Vec<Hands> = input
-        .lines()
-        .map(|line| {
-            let parts: Vec<&str> = line.split_whitespace().collect();
-            let cards: Vec<char> = parts[0].chars().collect();
-            let hand = Hand::new(cards.clone(), ranking_fn(cards));
-            let bidding = parts[1].parse::<usize>().unwrap();
-            (hand, bidding)
-        })
-        .collect();
-    hands
+fn total_winnings(hands: &[(&str, usize)], rules: &Rules) -> Option<usize> {
+    let mut hands = hands
+        .iter()
+        .map(|&(cards, bid)| Some((rules.hand(cards)?, bid)))
+        .collect::<Option<Vec<_>>>()?;
+    hands.sort_unstable();
+
+    Some(
+        hands
+            .iter()
+            .enumerate()
+            .map(|(idx, (_, bid))| bid * (idx + 1))
+            .sum(),
+    )
 }
 
-pub fn part_one(input: &str) -> Option<usize> {
-    let mut hands = parse_input(input, rank);
-
-    // Sorting hands based on rank
-    hands.sort_by(|a, b| cmp_hands(&a.0, &b.0, "AKQJT98765432"));
-
-    let mut total_winnings: usize = 0;
-    for (idx, hand) in hands.iter().rev().enumerate() {
-        //println!("{:?} - {:?} - {}", hand.0.rank, hand.0.cards, hand.1);
-        total_winnings += hand.1 * (idx + 1);
-    }
-    Some(total_winnings)
+pub fn part_one(hands: &[(&str, usize)]) -> Option<usize> {
+    total_winnings(hands, &STANDARD_RULES)
 }
 
-pub fn part_two(input: &str) -> Option<usize> {
-    let mut hands: Vec<Hands> = parse_input(input, rank_part_two);
-
-    // Sorting hands based on rank
-    hands.sort_by(|a, b| cmp_hands(&a.0, &b.0, "AKQT98765432J"));
-
-    // Display sorted hands
-    let mut total_winnings: usize = 0;
-    for (idx, hand) in hands.iter().rev().enumerate() {
-        //println!("{:?} - {:?} - {}", hand.0.rank, hand.0.cards, hand.1);
-        total_winnings += hand.1 * (idx + 1);
-    }
-    Some(total_winnings)
+pub fn part_two(hands: &[(&str, usize)]) -> Option<usize> {
+    total_winnings(hands, &JOKER_RULES)
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use advent_of_code::testing::Rng;
 
     #[test]
     fn test_part_one() {
-        let result = part_one(&advent_of_code::template::read_file("examples", DAY));
+        let input = advent_of_code::template::read_file("examples", DAY);
+        let result = part_one(&parse_input(&input).unwrap());
         assert_eq!(result, Some(6440));
     }
 
     #[test]
     fn test_part_two() {
-        let result = part_two(&advent_of_code::template::read_file("examples", DAY));
+        let input = advent_of_code::template::read_file("examples", DAY);
+        let result = part_two(&parse_input(&input).unwrap());
         assert_eq!(result, Some(5905));
     }
+
+    #[test]
+    fn test_rules() {
+        let rank = |rules: &Rules, cards| rules.hand(cards).unwrap().rank;
+        assert_eq!(rank(&STANDARD_RULES, "T55J5"), Rank::ThreeOfAKind);
+        assert_eq!(rank(&JOKER_RULES, "T55J5"), Rank::FourOfAKind);
+        assert_eq!(rank(&JOKER_RULES, "JJJJJ"), Rank::FiveOfAKind);
+        assert_eq!(rank(&JOKER_RULES, "KTJJT"), Rank::FourOfAKind);
+        assert!(JOKER_RULES.hand("JJJJJ") < JOKER_RULES.hand("22222"));
+        assert!(JOKER_RULES.hand("JKKK2") < JOKER_RULES.hand("QQQQ2"));
+        assert!(STANDARD_RULES.hand("12345").is_none());
+
+        // several wildcards and another alphabet
+        let rules = Rules::new("zyxwv*?", "*?");
+        assert_eq!(rank(&rules, "zy*?x"), Rank::ThreeOfAKind);
+        assert_eq!(rank(&rules, "zz*?y"), Rank::FourOfAKind);
+        assert_eq!(rank(&rules, "zzyy?"), Rank::FullHouse);
+        assert!(rules.hand("?zzzz") < rules.hand("*zzzz"));
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        let error = parse_input("32T3K 765\nT55X5 684").unwrap_err();
+        assert_eq!((error.line, error.column, error.text.as_str()), (2, 4, "X"));
+        let error = parse_input("32T3K").unwrap_err();
+        assert_eq!(error.expected, "cards and a bid");
+        assert!(parse_input("32T3K x").is_err());
+    }
+
+    #[test]
+    fn test_wildcards_pick_the_best_rank() {
+        let mut rng = Rng::new(0x243f_6a88_85a3_08d3);
+
+        for _ in 0..500 {
+            // draw from a few cards only, so that hands have groups.
+            let cards: String = (0..5).map(|_| rng.pick(b"AKTJ") as char).collect();
+
+            // try every card for every wildcard, `Q` stands in for all cards that are not in the hand.
+            let mut best = Rank::HighCard;
+            let mut candidates = vec![String::new()];
+            for card in cards.chars() {
+                let choices: Vec<char> = if card == 'J' {
+                    "AKTQ".chars().collect()
+                } else {
+                    vec![card]
+                };
+                candidates = candidates
+                    .iter()
+                    .flat_map(|prefix| choices.iter().map(move |&c| format!("{prefix}{c}")))
+                    .collect();
+            }
+            for candidate in candidates {
+                best = best.max(STANDARD_RULES.hand(&candidate).unwrap().rank);
+            }
+
+            assert_eq!(JOKER_RULES.hand(&cards).unwrap().rank, best, "{cards}");
+        }
+    }
 }
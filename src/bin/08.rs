@@ -1,137 +1,270 @@
 use std::collections::HashMap;
 
-advent_of_code::solution!(8);
+use advent_of_code::cycle;
+use advent_of_code::math;
+use advent_of_code::parse::{self, ParseError};
 
-type Node = String;
-type Left = String;
-type Right = String;
+advent_of_code::solution!(8, parse_input);
 
-fn parse_input(input: &str) -> (Vec<char>, HashMap<Node, (Left, Right)>) {
-    let mut chars = Vec::new();
-    let mut node_map = HashMap::<Node, (Left, Right)>::new();
+/// Index of a node in the compiled [`Network`]
+type NodeId = u32;
 
-    for line in input.lines() {
-        let tokens: Vec<&str> = line.split_whitespace().collect();
+/// The network compiled into dense node ids, so walking it never hashes or clones a name.
+#[derive(Debug)]
+pub struct Network {
+    /// 0 for left and 1 for right, one entry per instruction
+    instructions: Vec<usize>,
+    names: Vec<String>,
+    ids: HashMap<String, NodeId>,
+    /// The left and right successor of node `id` at `2 * id` and `2 * id + 1`
+    successors: Vec<NodeId>,
+    /// The node that node `id` ends up at after following every instruction once
+    jumps: Vec<NodeId>,
+}
 
-        if tokens.is_empty() {
-            // Skip empty lines
-            continue;
-        }
+impl Network {
+    fn id(&self, name: &str) -> Option<NodeId> {
+        self.ids.get(name).copied()
+    }
 
-        if tokens.len() == 1 {
-            // Process the first line to Vec<char>
-            chars = tokens[0].chars().collect();
-        } else {
-            // Process lines with the format: <string> = (<string>, <string>)
-            let label = tokens[0];
-            let left_label = tokens[2].trim_matches(|c| c == '(' || c == ',' || c == ')');
-            let right_label = tokens[3].trim_matches(|c| c == '(' || c == ',' || c == ')');
-
-            node_map
-                .entry(label.parse().unwrap())
-                .or_insert_with(|| (left_label.parse().unwrap(), right_label.parse().unwrap()));
-        }
+    fn step(&self, node: NodeId, instruction: usize) -> NodeId {
+        self.successors[2 * node as usize + self.instructions[instruction]]
     }
 
-    (chars, node_map)
-}
+    /// For every node, the steps within one pass over the instructions after which a walk
+    /// starting at that node is on a node matching `is_target`. Step 0 is the node itself.
+    fn hits(&self, is_target: impl Fn(&str) -> bool) -> Vec<Vec<u32>> {
+        let targets: Vec<bool> = self.names.iter().map(|name| is_target(name)).collect();
 
-fn get_next_node_from_mapping(
-    direction: char,
-    current_node: &Node,
-    node_mapping: &HashMap<Node, (Left, Right)>,
-) -> Node {
-    match direction {
-        'L' => node_mapping.get(current_node).unwrap().0.parse().unwrap(),
-        'R' => node_mapping.get(current_node).unwrap().1.parse().unwrap(),
-        _ => panic!("Invalid direction: {}", direction),
+        (0..self.names.len() as NodeId)
+            .map(|start| {
+                let mut node = start;
+                let mut hits = vec![];
+                for idx in 0..self.instructions.len() {
+                    if targets[node as usize] {
+                        hits.push(idx as u32);
+                    }
+                    node = self.step(node, idx);
+                }
+                hits
+            })
+            .collect()
     }
-}
 
-pub fn part_one(input: &str) -> Option<usize> {
-    let (directions, node_mapping) = parse_input(input);
-    //println!("directions: {:?}", directions);
-    //println!("node_mapping: {:?}", node_mapping);
-    let mut destination_found = false;
-    let mut steps: usize = 0;
-    let mut directions_idx: usize = 0;
-    let mut current_node: String = "AAA".to_string();
-    while !destination_found {
-        steps += 1;
-        let direction = directions[directions_idx % directions.len()];
-        let next_node: String = get_next_node_from_mapping(direction, &current_node, &node_mapping);
-        if next_node == "ZZZ" {
-            destination_found = true;
-        } else {
-            current_node = next_node;
+    /// Counts the steps from `start` to the first node with a hit, a whole pass over the instructions at a time.
+    /// Returns `None` if the walk never gets there.
+    fn count_steps(&self, start: NodeId, hits: &[Vec<u32>]) -> Option<usize> {
+        let mut node = start;
+        // a walk that did not hit anything after visiting every node at the start of a pass repeats forever.
+        for pass in 0..self.names.len() {
+            if let Some(&step) = hits[node as usize].first() {
+                return Some(pass * self.instructions.len() + step as usize);
+            }
+            node = self.jumps[node as usize];
         }
-        directions_idx += 1;
+        None
+    }
+
+    /// The nodes of the walk from `start` at the start of each pass over the instructions, up to the first repeat.
+    fn ghost_walk(&self, start: NodeId) -> Walk {
+        let passes = std::iter::successors(Some(start), |&node| Some(self.jumps[node as usize]));
+        cycle::detect(passes).expect("a walk through finitely many nodes repeats")
     }
-    Some(steps)
 }
 
-fn find_nodes_ending_with_a(map: &HashMap<Node, (Left, Right)>) -> Vec<Node> {
-    let mut result = Vec::new();
+fn parse_input(input: &str) -> Result<Network, ParseError> {
+    let mut lines = input.lines();
+    let line = lines
+        .next()
+        .ok_or_else(|| ParseError::at_end(input, "a line of instructions"))?
+        .trim_end();
+    if line.is_empty() {
+        return Err(ParseError::at(input, line, "at least one instruction"));
+    }
+    let instructions = line
+        .char_indices()
+        .map(|(idx, c)| match c {
+            'L' => Ok(0),
+            'R' => Ok(1),
+            _ => Err(ParseError::at(
+                input,
+                &line[idx..idx + c.len_utf8()],
+                "`L` or `R`",
+            )),
+        })
+        .collect::<Result<_, _>>()?;
+
+    // lines with the format: <string> = (<string>, <string>)
+    let definitions = lines
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| {
+            let (label, successors) =
+                parse::assignment::<2>(line).map_err(|err| err.located_in(input, line))?;
+            Ok((label, successors, line))
+        })
+        .collect::<Result<Vec<_>, ParseError>>()?;
 
-    for (node, (_left, _right)) in map.iter() {
-        if node.ends_with('A') {
-            result.push(node.clone());
+    // nodes are numbered in the order in which they are defined.
+    let mut names: Vec<String> = vec![];
+    let mut ids: HashMap<String, NodeId> = HashMap::new();
+    for &(label, _, line) in &definitions {
+        let id = NodeId::try_from(names.len())
+            .map_err(|_| ParseError::at(input, line, "fewer nodes"))?;
+        if ids.insert(label.to_string(), id).is_some() {
+            return Err(ParseError::at(input, line, "each node to be defined once"));
         }
+        names.push(label.to_string());
     }
-    result
-}
 
-fn gcd(mut a: usize, mut b: usize) -> usize {
-    while b != 0 {
-        let temp = b;
-        b = a % b;
-        a = temp;
+    let mut successors = Vec::with_capacity(2 * names.len());
+    for (_, pair, line) in definitions {
+        for name in pair {
+            let id = ids.get(name).ok_or_else(|| {
+                let usage = line
+                    .rfind(name)
+                    .map_or(line, |idx| &line[idx..idx + name.len()]);
+                ParseError::at(input, usage, "a node that is defined")
+            })?;
+            successors.push(*id);
+        }
     }
-    a
+
+    let mut network = Network {
+        instructions,
+        names,
+        ids,
+        successors,
+        jumps: vec![],
+    };
+    network.jumps = (0..network.names.len() as NodeId)
+        .map(|start| {
+            (0..network.instructions.len()).fold(start, |node, idx| network.step(node, idx))
+        })
+        .collect();
+
+    Ok(network)
+}
+
+pub fn part_one(network: &Network) -> Option<usize> {
+    let hits = network.hits(|node| node == "ZZZ");
+    network.count_steps(network.id("AAA")?, &hits)
+}
+
+/// The walk of a ghost, see [`Network::ghost_walk`].
+type Walk = cycle::History<NodeId>;
+
+/// The steps after which the ghost on `walk` is on a node with a hit, for every step before the walk repeats.
+fn arrivals(walk: &Walk, hits: &[Vec<u32>], pass_length: usize) -> Vec<usize> {
+    walk.states()
+        .iter()
+        .enumerate()
+        .flat_map(|(pass, &node)| {
+            hits[node as usize]
+                .iter()
+                .map(move |&step| pass * pass_length + step as usize)
+        })
+        .collect()
+}
+
+/// Whether the ghost on `walk` is on a node with a hit after `steps` steps.
+fn is_arrival(walk: &Walk, hits: &[Vec<u32>], pass_length: usize, steps: usize) -> bool {
+    let node = *walk.state_at(steps / pass_length);
+    hits[node as usize]
+        .binary_search(&((steps % pass_length) as u32))
+        .is_ok()
+}
+
+/// The number of steps `T` after which the ghost is on a `..Z` node for the first time,
+/// if it is on a `..Z` node exactly at the multiples of `T`.
+fn arrival_period(walk: &Walk, hits: &[Vec<u32>], pass_length: usize) -> Option<usize> {
+    let arrivals = arrivals(walk, hits, pass_length);
+    let period = *arrivals.first().filter(|&&t| t > 0)?;
+
+    let is_periodic = (walk.cycle.length * pass_length).is_multiple_of(period)
+        && arrivals
+            .iter()
+            .copied()
+            .eq((period..walk.cycle.end() * pass_length).step_by(period));
+    is_periodic.then_some(period)
 }
 
-fn lcm(a: usize, b: usize) -> usize {
-    if a == 0 || b == 0 {
-        0
-    } else {
-        (a * b) / gcd(a, b)
+pub fn part_two(network: &Network) -> Option<usize> {
+    let pass_length = network.instructions.len();
+    let hits = network.hits(|node| node.ends_with('Z'));
+    let walks: Vec<Walk> = network
+        .names
+        .iter()
+        .filter(|name| name.ends_with('A'))
+        .map(|name| network.ghost_walk(network.ids[name]))
+        .collect();
+    if walks.is_empty() {
+        return None;
+    }
+
+    // if every ghost arrives periodically, they all arrive together at the lcm of their periods.
+    if let Some(periods) = walks
+        .iter()
+        .map(|walk| arrival_period(walk, &hits, pass_length))
+        .collect::<Option<Vec<_>>>()
+    {
+        return math::lcm_all(periods);
     }
+
+    first_common_arrival(&walks, &hits, pass_length)
 }
 
-pub fn part_two(input: &str) -> Option<usize> {
-    let (directions, node_mapping) = parse_input(input);
-    //println!("directions: {:?}", directions);
-    //println!("node_mapping: {:?}", node_mapping);
-    let current_nodes: Vec<Node> = find_nodes_ending_with_a(&node_mapping);
-    let mut node_steps: Vec<usize> = Vec::new();
-
-    //println!("starting nodes: {:?}", current_nodes);
-    for node in current_nodes.iter() {
-        let mut steps: usize = 0;
-        let mut directions_idx: usize = 0;
-        let mut current_node = node.clone();
-        let mut destination_found = false;
-        while !destination_found {
-            steps += 1;
-            let direction = directions[directions_idx % directions.len()];
-            let next_node: String =
-                get_next_node_from_mapping(direction, &current_node, &node_mapping);
-            if next_node.ends_with('Z') {
-                destination_found = true;
-            } else {
-                current_node = next_node;
-            }
-            directions_idx += 1;
-        }
-        node_steps.push(steps);
+/// The first step after which every ghost is on a node with a hit, for ghosts that do not arrive periodically.
+fn first_common_arrival(walks: &[Walk], hits: &[Vec<u32>], pass_length: usize) -> Option<usize> {
+    // from this step on, every ghost is on its cycle.
+    let latest = walks.iter().max_by_key(|walk| walk.cycle.start)?;
+    let cycle_start = latest.cycle.start * pass_length;
+
+    // before that, only the arrivals of the ghost that enters its cycle last are candidates.
+    let early = arrivals(latest, hits, pass_length)
+        .into_iter()
+        .filter(|&steps| steps > 0 && steps < cycle_start)
+        .find(|&steps| {
+            walks
+                .iter()
+                .all(|walk| is_arrival(walk, hits, pass_length, steps))
+        });
+    if early.is_some() {
+        return early;
     }
-    let mut result = node_steps[0];
 
-    for &num in node_steps.iter().skip(1) {
-        result = lcm(result, num);
+    // on its cycle, a ghost arrives at a few offsets modulo the length of the cycle.
+    // every choice of one offset per ghost is a system of congruences.
+    let mut solutions: Vec<(i64, i64)> = vec![(0, 1)];
+    for walk in walks {
+        let modulus = i64::try_from(walk.cycle.length * pass_length).ok()?;
+        let offsets: Vec<i64> = arrivals(walk, hits, pass_length)
+            .into_iter()
+            .filter(|&steps| steps >= walk.cycle.start * pass_length)
+            .map(|steps| steps as i64)
+            .collect();
+        solutions = solutions
+            .iter()
+            .flat_map(|&solution| {
+                offsets
+                    .iter()
+                    .filter_map(move |&offset| math::crt([solution, (offset, modulus)]))
+            })
+            .collect();
+        solutions.sort_unstable();
+        solutions.dedup();
     }
 
-    Some(result)
+    // the smallest solution that is not before the start of the last cycle.
+    let lowest = cycle_start.max(1) as i64;
+    solutions
+        .into_iter()
+        .filter_map(|(x, modulus)| {
+            let behind = (lowest - x).max(0);
+            let laps = (behind + modulus - 1) / modulus;
+            x.checked_add(laps.checked_mul(modulus)?)
+        })
+        .min()
+        .map(|steps| steps as usize)
 }
 
 #[cfg(test)]
@@ -140,13 +273,93 @@ mod tests {
 
     #[test]
     fn test_part_one() {
-        let result = part_one(&advent_of_code::template::read_file("examples", DAY));
+        let input = advent_of_code::template::read_file("examples", DAY);
+        let result = part_one(&parse_input(&input).unwrap());
         assert_eq!(result, Some(6));
     }
 
     #[test]
     fn test_part_two() {
-        let result = part_two(&advent_of_code::template::read_file("examples", DAY));
+        let input = advent_of_code::template::read_file("examples", DAY);
+        let result = part_two(&parse_input(&input).unwrap());
         assert_eq!(result, Some(6));
     }
+
+    #[test]
+    fn test_part_two_periodic_ghosts() {
+        let input = "LR
+
+11A = (11B, XXX)
+11B = (XXX, 11Z)
+11Z = (11B, XXX)
+22A = (22B, XXX)
+22B = (22C, 22C)
+22C = (22Z, 22Z)
+22Z = (22B, 22B)
+XXX = (XXX, XXX)
+";
+        let network = parse_input(input).unwrap();
+        let hits = network.hits(|node| node.ends_with('Z'));
+        let walk = network.ghost_walk(network.id("22A").unwrap());
+        assert_eq!(arrival_period(&walk, &hits, 2), Some(3));
+        assert_eq!(part_two(&network), Some(6));
+
+        // the walk through the compiled network matches stepping one instruction at a time.
+        for steps in 0..50 {
+            let node = (0..steps).fold(network.id("22A").unwrap(), |node, idx| {
+                network.step(node, idx % 2)
+            });
+            assert_eq!(
+                is_arrival(&walk, &hits, 2, steps),
+                network.names[node as usize].ends_with('Z')
+            );
+        }
+    }
+
+    #[test]
+    fn test_part_two_aperiodic_ghosts() {
+        // the first ghost arrives after 2, 4, 6, ... steps, the second one after 1, 4, 7, ... steps.
+        let input = "L
+
+11A = (11B, 11B)
+11B = (11Z, 11Z)
+11Z = (11B, 11B)
+22A = (22Z, 22Z)
+22Z = (22B, 22B)
+22B = (22C, 22C)
+22C = (22Z, 22Z)
+";
+        let network = parse_input(input).unwrap();
+        let hits = network.hits(|node| node.ends_with('Z'));
+        let walk = network.ghost_walk(network.id("22A").unwrap());
+        assert_eq!(arrival_period(&walk, &hits, 1), None);
+        assert_eq!(part_two(&network), Some(4));
+
+        // a ghost that only arrives before its cycle, and one that never arrives.
+        let input = "LR
+
+11A = (11Z, 11Z)
+11Z = (11B, 11B)
+11B = (11B, 11B)
+22A = (22Z, 22B)
+22B = (22B, 22B)
+22Z = (22B, 22B)
+";
+        let network = parse_input(input).unwrap();
+        assert_eq!(part_two(&network), Some(1));
+        let input = input.replace("22A = (22Z, 22B)", "22A = (22B, 22B)");
+        assert_eq!(part_two(&parse_input(&input).unwrap()), None);
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        let error = parse_input("LR\n\nAAA = (BBB, AAA)\n").unwrap_err();
+        assert_eq!(
+            (error.line, error.expected.as_str()),
+            (3, "a node that is defined")
+        );
+        assert!(parse_input("LU\n\nAAA = (AAA, AAA)\n").is_err());
+        assert!(parse_input("LW\n\nAAA = (AAA, AAA)\n").is_err());
+        assert!(parse_input("\n\nAAA = (AAA, AAA)\n").is_err());
+    }
 }
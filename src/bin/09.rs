@@ -1,65 +1,30 @@
-advent_of_code::solution!(9);
+use advent_of_code::parse::{self, ParseError};
+use advent_of_code::sequence::Polynomial;
 
-fn get_pairwise_diff(mut data: Vec<isize>) -> Vec<Vec<isize>> {
-    let mut diff_history = Vec::new();
-    diff_history.push(data.clone());
+advent_of_code::solution!(9, parse_input);
 
-    while data.iter().any(|&x| x != 0) {
-        let mut current_diff = Vec::new();
-        for i in 0..data.len() - 1 {
-            let diff = data[i + 1] - data[i];
-            current_diff.push(diff);
-            data[i] = diff;
-        }
-        data.pop();
-        diff_history.push(current_diff.clone());
-        //println!("pairwise diff: {:?}", current_diff);
-    }
-    diff_history
+fn parse_input(input: &str) -> Result<Vec<Vec<isize>>, ParseError> {
+    parse::lines_with(input, parse::words_of)
 }
 
-fn parse_input(input: &str) -> Vec<Vec<isize>> {
-    return input
-        .lines()
-        .map(|line| {
-            //println!("line: {}", &line);
-            line.split_whitespace()
-                .map(|reading| reading.parse::<isize>().unwrap())
-                .collect()
-        })
-        .collect();
+/// Sums up the value of every sequence picked by `predict`.
+/// Returns `None` if a sequence cannot be extrapolated or the sum overflows.
+fn sum_predictions(
+    sensor_data: &[Vec<isize>],
+    predict: impl Fn(&Polynomial<isize>) -> Option<isize>,
+) -> Option<isize> {
+    sensor_data.iter().try_fold(0_isize, |sum, data| {
+        let prediction = predict(&Polynomial::fit(data).ok()?)?;
+        sum.checked_add(prediction)
+    })
 }
 
-pub fn part_one(input: &str) -> Option<isize> {
-    let sensor_data: Vec<Vec<isize>> = parse_input(input);
-    let prediction: isize = sensor_data
-        .iter()
-        .map(|data| get_pairwise_diff(data.clone()))
-        .map(|diff_history| {
-            diff_history
-                .iter()
-                .rev()
-                .filter_map(|diff| diff.last().cloned())
-                .sum::<isize>()
-        })
-        .sum();
-    Some(prediction)
+pub fn part_one(sensor_data: &[Vec<isize>]) -> Option<isize> {
+    sum_predictions(sensor_data, |sequence| sequence.forward(1))
 }
 
-pub fn part_two(input: &str) -> Option<isize> {
-    let sensor_data: Vec<Vec<isize>> = parse_input(input);
-    let prediction: isize = sensor_data
-        .iter()
-        .map(|data| get_pairwise_diff(data.clone()))
-        .map(|diff_history| {
-            diff_history
-                .iter()
-                .rev()
-                .filter_map(|diff| diff.first().cloned())
-                .fold(0, |acc, x| x - acc)
-        })
-        .sum();
-    Some(prediction)
+pub fn part_two(sensor_data: &[Vec<isize>]) -> Option<isize> {
+    sum_predictions(sensor_data, |sequence| sequence.backward(1))
 }
 
 #[cfg(test)]
@@ -68,13 +33,22 @@ mod tests {
 
     #[test]
     fn test_part_one() {
-        let result = part_one(&advent_of_code::template::read_file("examples", DAY));
+        let input = advent_of_code::template::read_file("examples", DAY);
+        let result = part_one(&parse_input(&input).unwrap());
         assert_eq!(result, Some(114));
     }
 
     #[test]
     fn test_part_two() {
-        let result = part_two(&advent_of_code::template::read_file("examples", DAY));
+        let input = advent_of_code::template::read_file("examples", DAY);
+        let result = part_two(&parse_input(&input).unwrap());
         assert_eq!(result, Some(2));
     }
+
+    #[test]
+    fn test_unpredictable_sequence() {
+        let sensor_data = parse_input("0 3 6 9 12 15\n1 2 4 8\n").unwrap();
+        assert_eq!(part_one(&sensor_data[..1]), Some(18));
+        assert_eq!(part_one(&sensor_data), None);
+    }
 }
@@ -0,0 +1,254 @@
+//! Cycle detection for simulations that eventually repeat a state
+//!
+//! A sequence `x0, x1 = step(x0), x2 = step(x1), ...` over a finite set of states always ends up in a loop.
+//! [`Cycle`] describes it by the index of the first state on the loop and the length of the loop,
+//! which is enough to tell the state at any step without simulating it.
+//!
+//! [`floyd`] and [`brent`] need constant memory but have to call `step` several times per state.
+//! [`detect`] remembers every state it has seen, works on any iterator and can look up states afterwards.
+//!
+//! ```
+//! use advent_of_code::cycle;
+//!
+//! // 3 -> 9 -> 27 -> 81 -> 43 -> 29 -> 87 -> 61 -> 83 -> 49 -> 47 -> 41 -> 23 -> 69 -> 7 -> 21 -> 63 -> 89 -> 67 -> 1 -> 3
+//! let step = |x: &u32| x * 3 % 100;
+//!
+//! let cycle = cycle::brent(3, step);
+//! assert_eq!((cycle.start, cycle.length), (0, 20));
+//! assert_eq!(cycle::state_at(3, step, 1_000_000_000), 3);
+//! ```
+
+use std::collections::HashMap;
+use std::hash::Hash;
+
+/// The shape of an eventually periodic sequence: the states from index `start` on repeat every `length` steps.
+#[derive(PartialEq, Eq, Hash, Clone, Copy, Debug)]
+pub struct Cycle {
+    pub start: usize,
+    pub length: usize,
+}
+
+impl Cycle {
+    /// The index of the first repeated state, i.e. `start + length`
+    pub fn end(&self) -> usize {
+        self.start + self.length
+    }
+
+    /// The smallest index whose state equals the state at index `n`
+    pub fn reduce(&self, n: usize) -> usize {
+        if n < self.start {
+            n
+        } else {
+            self.start + (n - self.start) % self.length
+        }
+    }
+}
+
+/// The states of a sequence up to its first repetition, see [`detect`].
+#[derive(Clone, Debug)]
+pub struct History<S> {
+    pub cycle: Cycle,
+    states: Vec<S>,
+}
+
+impl<S> History<S> {
+    /// The states at the indices `0..cycle.end()`
+    pub fn states(&self) -> &[S] {
+        &self.states
+    }
+
+    /// The state at index `n` of the sequence
+    pub fn state_at(&self, n: usize) -> &S {
+        &self.states[self.cycle.reduce(n)]
+    }
+
+    /// The indices in `0..cycle.end()` whose states match `predicate`
+    pub fn positions(&self, mut predicate: impl FnMut(&S) -> bool) -> Vec<usize> {
+        (0..self.states.len())
+            .filter(|&idx| predicate(&self.states[idx]))
+            .collect()
+    }
+}
+
+/// Consumes `states` until a state repeats.
+/// Returns `None` if the iterator ends before that, i.e. the sequence is not periodic.
+pub fn detect<S: Clone + Eq + Hash>(states: impl IntoIterator<Item = S>) -> Option<History<S>> {
+    let mut seen: HashMap<S, usize> = HashMap::new();
+    let mut history = vec![];
+
+    for (idx, state) in states.into_iter().enumerate() {
+        if let Some(&start) = seen.get(&state) {
+            return Some(History {
+                cycle: Cycle {
+                    start,
+                    length: idx - start,
+                },
+                states: history,
+            });
+        }
+        seen.insert(state.clone(), idx);
+        history.push(state);
+    }
+
+    None
+}
+
+/// The state after `n` calls of `step`, skipping over full cycles once the sequence repeats.
+pub fn state_at<S: Clone + Eq + Hash>(start: S, mut step: impl FnMut(&S) -> S, n: usize) -> S {
+    let mut seen: HashMap<S, usize> = HashMap::new();
+    let mut history = vec![];
+    let mut state = start;
+
+    for idx in 0..n {
+        if let Some(&cycle_start) = seen.get(&state) {
+            let cycle = Cycle {
+                start: cycle_start,
+                length: idx - cycle_start,
+            };
+            return history.swap_remove(cycle.reduce(n));
+        }
+        seen.insert(state.clone(), idx);
+        let next = step(&state);
+        history.push(state);
+        state = next;
+    }
+
+    state
+}
+
+/// Floyd's tortoise and hare algorithm.
+/// Never returns if the sequence starting at `start` does not repeat.
+pub fn floyd<S: Clone + Eq>(start: S, mut step: impl FnMut(&S) -> S) -> Cycle {
+    let mut tortoise = step(&start);
+    let mut hare = step(&tortoise);
+    while tortoise != hare {
+        tortoise = step(&tortoise);
+        hare = step(&hare);
+        hare = step(&hare);
+    }
+
+    // the hare is now a multiple of the cycle length ahead, so both meet at the start of the cycle.
+    let mut cycle_start = 0;
+    tortoise = start;
+    while tortoise != hare {
+        tortoise = step(&tortoise);
+        hare = step(&hare);
+        cycle_start += 1;
+    }
+
+    let mut length = 1;
+    hare = step(&tortoise);
+    while tortoise != hare {
+        hare = step(&hare);
+        length += 1;
+    }
+
+    Cycle {
+        start: cycle_start,
+        length,
+    }
+}
+
+/// Brent's algorithm, usually needs fewer calls of `step` than [`floyd`].
+/// Never returns if the sequence starting at `start` does not repeat.
+pub fn brent<S: Clone + Eq>(start: S, mut step: impl FnMut(&S) -> S) -> Cycle {
+    let mut power = 1;
+    let mut length = 1;
+    let mut tortoise = start.clone();
+    let mut hare = step(&start);
+    while tortoise != hare {
+        if power == length {
+            tortoise = hare.clone();
+            power *= 2;
+            length = 0;
+        }
+        hare = step(&hare);
+        length += 1;
+    }
+
+    // start the hare one cycle length ahead, both meet at the start of the cycle.
+    let mut tortoise = start.clone();
+    let mut hare = start;
+    for _ in 0..length {
+        hare = step(&hare);
+    }
+    let mut cycle_start = 0;
+    while tortoise != hare {
+        tortoise = step(&tortoise);
+        hare = step(&hare);
+        cycle_start += 1;
+    }
+
+    Cycle {
+        start: cycle_start,
+        length,
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::{brent, detect, floyd, state_at, Cycle};
+    use crate::testing::Rng;
+
+    #[test]
+    fn reduces_indices() {
+        let cycle = Cycle {
+            start: 3,
+            length: 4,
+        };
+        assert_eq!(cycle.end(), 7);
+        assert_eq!(cycle.reduce(2), 2);
+        assert_eq!(cycle.reduce(7), 3);
+        assert_eq!(cycle.reduce(1_000_000), 4);
+    }
+
+    #[test]
+    fn detects_prefix_and_length() {
+        // 0 -> 1 -> 2 -> 3 -> 4 -> 5 -> 2
+        let step = |&x: &u32| if x == 5 { 2 } else { x + 1 };
+        let expected = Cycle {
+            start: 2,
+            length: 4,
+        };
+        assert_eq!(floyd(0, step), expected);
+        assert_eq!(brent(0, step), expected);
+
+        let history = detect(std::iter::successors(Some(0), |x| Some(step(x)))).unwrap();
+        assert_eq!(history.cycle, expected);
+        assert_eq!(history.states(), [0, 1, 2, 3, 4, 5]);
+        assert_eq!(*history.state_at(10), 2);
+        assert_eq!(history.positions(|&x| x % 2 == 1), [1, 3, 5]);
+
+        assert_eq!(state_at(0, step, 0), 0);
+        assert_eq!(state_at(0, step, 5), 5);
+        assert_eq!(state_at(0, step, 10), 2);
+    }
+
+    #[test]
+    fn finite_iterators_have_no_cycle() {
+        assert!(detect(0..100).is_none());
+    }
+
+    #[test]
+    fn algorithms_agree_on_random_functions() {
+        let mut rng = Rng::new(0x2545_f491_4f6c_dd1d);
+
+        for _ in 0..200 {
+            let size = (rng.below(50) + 1) as usize;
+            let mapping: Vec<usize> = (0..size).map(|_| rng.below(size as u64) as usize).collect();
+            let start = rng.below(size as u64) as usize;
+            let step = |&x: &usize| mapping[x];
+
+            let history = detect(std::iter::successors(Some(start), |x| Some(step(x)))).unwrap();
+            assert_eq!(floyd(start, step), history.cycle);
+            assert_eq!(brent(start, step), history.cycle);
+
+            let n = rng.below(1000) as usize;
+            let simulated = (0..n).fold(start, |x, _| step(&x));
+            assert_eq!(state_at(start, step, n), simulated);
+            assert_eq!(*history.state_at(n), simulated);
+        }
+    }
+}
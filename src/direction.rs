@@ -0,0 +1,421 @@
+//! Directions on a grid, using the same screen coordinates as [`Point`]
+//!
+//! [`Direction`] covers the four orthogonal directions and [`Direction8`] adds the diagonals.
+//! Both can be parsed from the notations commonly used by puzzle inputs and added to points.
+//!
+//! ```
+//! use advent_of_code::direction::Direction;
+//! use advent_of_code::point::Point;
+//!
+//! let moves: Vec<Direction> = "R^L".chars().map(|c| c.try_into().unwrap()).collect();
+//! assert_eq!(moves, [Direction::Right, Direction::Up, Direction::Left]);
+//!
+//! let mut position = Point::new(0, 0);
+//! for direction in moves {
+//!     position += direction;
+//! }
+//! assert_eq!(position, Point::new(0, -1));
+//! assert_eq!(Direction::Up.turn_right(), Direction::Right);
+//! ```
+
+use std::error::Error;
+use std::fmt::Display;
+use std::ops::{Add, AddAssign, Sub, SubAssign};
+use std::str::FromStr;
+
+use crate::point::{Point, SignedCoordinate};
+
+/// One of the four orthogonal directions.
+#[derive(PartialEq, Eq, PartialOrd, Ord, Hash, Clone, Copy, Debug)]
+pub enum Direction {
+    Up,
+    Right,
+    Down,
+    Left,
+}
+
+impl Direction {
+    /// All directions in clockwise order, starting with [`Direction::Up`]
+    pub const ALL: [Direction; 4] = [
+        Direction::Up,
+        Direction::Right,
+        Direction::Down,
+        Direction::Left,
+    ];
+
+    /// Iterates over all directions in clockwise order
+    pub fn iter() -> impl Iterator<Item = Direction> {
+        Self::ALL.into_iter()
+    }
+
+    /// The direction after turning 90° counter-clockwise
+    #[must_use]
+    pub fn turn_left(self) -> Self {
+        Self::ALL[(self as usize + 3) % 4]
+    }
+
+    /// The direction after turning 90° clockwise
+    #[must_use]
+    pub fn turn_right(self) -> Self {
+        Self::ALL[(self as usize + 1) % 4]
+    }
+
+    #[must_use]
+    pub fn opposite(self) -> Self {
+        Self::ALL[(self as usize + 2) % 4]
+    }
+
+    /// The offset of a single step in this direction
+    pub fn to_point<T: SignedCoordinate>(self) -> Point<T> {
+        Direction8::from(self).to_point()
+    }
+
+    /// The arrow representing the direction, i.e. one of `^>v<`
+    pub fn to_char(self) -> char {
+        match self {
+            Direction::Up => '^',
+            Direction::Right => '>',
+            Direction::Down => 'v',
+            Direction::Left => '<',
+        }
+    }
+}
+
+/// Parses `U/R/D/L`, `N/E/S/W` and `^>v<`.
+impl TryFrom<char> for Direction {
+    type Error = DirectionFromStrError;
+
+    fn try_from(c: char) -> Result<Self, Self::Error> {
+        match c {
+            'U' | 'N' | '^' => Ok(Direction::Up),
+            'R' | 'E' | '>' => Ok(Direction::Right),
+            'D' | 'S' | 'v' => Ok(Direction::Down),
+            'L' | 'W' | '<' => Ok(Direction::Left),
+            _ => Err(DirectionFromStrError),
+        }
+    }
+}
+
+impl FromStr for Direction {
+    type Err = DirectionFromStrError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut chars = s.trim().chars();
+        match (chars.next(), chars.next()) {
+            (Some(c), None) => c.try_into(),
+            _ => Err(DirectionFromStrError),
+        }
+    }
+}
+
+impl Display for Direction {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.to_char())
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+/// One of the four orthogonal or four diagonal directions.
+#[derive(PartialEq, Eq, PartialOrd, Ord, Hash, Clone, Copy, Debug)]
+pub enum Direction8 {
+    Up,
+    UpRight,
+    Right,
+    DownRight,
+    Down,
+    DownLeft,
+    Left,
+    UpLeft,
+}
+
+impl Direction8 {
+    /// All directions in clockwise order, starting with [`Direction8::Up`]
+    pub const ALL: [Direction8; 8] = [
+        Direction8::Up,
+        Direction8::UpRight,
+        Direction8::Right,
+        Direction8::DownRight,
+        Direction8::Down,
+        Direction8::DownLeft,
+        Direction8::Left,
+        Direction8::UpLeft,
+    ];
+
+    /// Iterates over all directions in clockwise order
+    pub fn iter() -> impl Iterator<Item = Direction8> {
+        Self::ALL.into_iter()
+    }
+
+    /// The direction after turning 90° counter-clockwise
+    #[must_use]
+    pub fn turn_left(self) -> Self {
+        Self::ALL[(self as usize + 6) % 8]
+    }
+
+    /// The direction after turning 90° clockwise
+    #[must_use]
+    pub fn turn_right(self) -> Self {
+        Self::ALL[(self as usize + 2) % 8]
+    }
+
+    /// The direction after turning 45° counter-clockwise
+    #[must_use]
+    pub fn turn_half_left(self) -> Self {
+        Self::ALL[(self as usize + 7) % 8]
+    }
+
+    /// The direction after turning 45° clockwise
+    #[must_use]
+    pub fn turn_half_right(self) -> Self {
+        Self::ALL[(self as usize + 1) % 8]
+    }
+
+    #[must_use]
+    pub fn opposite(self) -> Self {
+        Self::ALL[(self as usize + 4) % 8]
+    }
+
+    pub fn is_diagonal(self) -> bool {
+        self as usize % 2 == 1
+    }
+
+    /// The offset of a single step in this direction
+    pub fn to_point<T: SignedCoordinate>(self) -> Point<T> {
+        let (zero, one) = (T::ZERO, T::ONE);
+        let (x, y) = match self {
+            Direction8::Up => (zero, -one),
+            Direction8::UpRight => (one, -one),
+            Direction8::Right => (one, zero),
+            Direction8::DownRight => (one, one),
+            Direction8::Down => (zero, one),
+            Direction8::DownLeft => (-one, one),
+            Direction8::Left => (-one, zero),
+            Direction8::UpLeft => (-one, -one),
+        };
+        Point::new(x, y)
+    }
+
+    /// The compass notation of the direction, e.g. `NE`
+    pub fn to_compass(self) -> &'static str {
+        match self {
+            Direction8::Up => "N",
+            Direction8::UpRight => "NE",
+            Direction8::Right => "E",
+            Direction8::DownRight => "SE",
+            Direction8::Down => "S",
+            Direction8::DownLeft => "SW",
+            Direction8::Left => "W",
+            Direction8::UpLeft => "NW",
+        }
+    }
+}
+
+impl From<Direction> for Direction8 {
+    fn from(direction: Direction) -> Self {
+        Direction8::ALL[direction as usize * 2]
+    }
+}
+
+/// Fails for diagonal directions.
+impl TryFrom<Direction8> for Direction {
+    type Error = NotOrthogonalError;
+
+    fn try_from(direction: Direction8) -> Result<Self, Self::Error> {
+        if direction.is_diagonal() {
+            return Err(NotOrthogonalError(direction));
+        }
+        Ok(Direction::ALL[direction as usize / 2])
+    }
+}
+
+/// Parses the orthogonal directions only, see [`Direction`].
+impl TryFrom<char> for Direction8 {
+    type Error = DirectionFromStrError;
+
+    fn try_from(c: char) -> Result<Self, Self::Error> {
+        Direction::try_from(c).map(Direction8::from)
+    }
+}
+
+/// Parses anything [`Direction`] parses, plus the diagonals in compass (`NE`) or up/down (`UR`) notation.
+impl FromStr for Direction8 {
+    type Err = DirectionFromStrError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim() {
+            "NE" | "UR" => Ok(Direction8::UpRight),
+            "SE" | "DR" => Ok(Direction8::DownRight),
+            "SW" | "DL" => Ok(Direction8::DownLeft),
+            "NW" | "UL" => Ok(Direction8::UpLeft),
+            s => s.parse::<Direction>().map(Direction8::from),
+        }
+    }
+}
+
+impl Display for Direction8 {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.to_compass())
+    }
+}
+
+/// An error which can be returned when parsing a direction.
+#[derive(Debug)]
+pub struct DirectionFromStrError;
+
+impl Error for DirectionFromStrError {}
+
+impl Display for DirectionFromStrError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("expecting a direction, e.g. `U`, `N` or `^`")
+    }
+}
+
+/// An error which can be returned when converting a diagonal [`Direction8`] into a [`Direction`].
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct NotOrthogonalError(pub Direction8);
+
+impl Error for NotOrthogonalError {}
+
+impl Display for NotOrthogonalError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} is not one of the four orthogonal directions", self.0)
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+macro_rules! impl_point_ops {
+    ($($direction:ty),*) => {
+        $(
+            impl<T: SignedCoordinate> From<$direction> for Point<T> {
+                fn from(direction: $direction) -> Self {
+                    direction.to_point()
+                }
+            }
+
+            impl<T: SignedCoordinate> Add<$direction> for Point<T> {
+                type Output = Point<T>;
+
+                fn add(self, direction: $direction) -> Self {
+                    self + direction.to_point()
+                }
+            }
+
+            impl<T: SignedCoordinate> AddAssign<$direction> for Point<T> {
+                fn add_assign(&mut self, direction: $direction) {
+                    *self = *self + direction;
+                }
+            }
+
+            impl<T: SignedCoordinate> Sub<$direction> for Point<T> {
+                type Output = Point<T>;
+
+                fn sub(self, direction: $direction) -> Self {
+                    self - direction.to_point()
+                }
+            }
+
+            impl<T: SignedCoordinate> SubAssign<$direction> for Point<T> {
+                fn sub_assign(&mut self, direction: $direction) {
+                    *self = *self - direction;
+                }
+            }
+        )*
+    };
+}
+
+impl_point_ops!(Direction, Direction8);
+
+/* -------------------------------------------------------------------------- */
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::{Direction, Direction8, NotOrthogonalError};
+    use crate::point::{Point, DIAGONALS, ORTHOGONALS};
+
+    #[test]
+    fn turning() {
+        for direction in Direction::iter() {
+            assert_eq!(direction.turn_left().turn_right(), direction);
+            assert_eq!(direction.turn_right().turn_right(), direction.opposite());
+            assert_eq!(
+                direction.turn_right().to_point::<i32>(),
+                direction.to_point::<i32>().rotate_right()
+            );
+        }
+        for direction in Direction8::iter() {
+            assert_eq!(direction.turn_half_right().turn_half_left(), direction);
+            assert_eq!(
+                direction.turn_half_right().turn_half_right(),
+                direction.turn_right()
+            );
+            assert_eq!(direction.turn_left().turn_left(), direction.opposite());
+            assert_eq!(
+                direction.opposite().to_point::<i32>(),
+                -direction.to_point::<i32>()
+            );
+        }
+        assert_eq!(Direction8::Up.turn_half_left(), Direction8::UpLeft);
+    }
+
+    #[test]
+    fn matches_point_constants() {
+        let points: Vec<Point> = Direction::iter().map(Point::from).collect();
+        assert_eq!(points, ORTHOGONALS);
+
+        let mut points: Vec<Point> = Direction8::iter().map(Point::from).collect();
+        points.sort();
+        assert_eq!(points, DIAGONALS);
+    }
+
+    #[test]
+    fn parsing() {
+        for (notation, expected) in ["URDL", "NESW", "^>v<"]
+            .into_iter()
+            .flat_map(|notation| notation.chars().zip(Direction::ALL))
+        {
+            assert_eq!(Direction::try_from(notation).unwrap(), expected);
+            assert_eq!(notation.to_string().parse::<Direction>().unwrap(), expected);
+        }
+        assert!(Direction::try_from('x').is_err());
+        assert!("UR".parse::<Direction>().is_err());
+        assert!("".parse::<Direction>().is_err());
+
+        assert_eq!("NE".parse::<Direction8>().unwrap(), Direction8::UpRight);
+        assert_eq!("DL".parse::<Direction8>().unwrap(), Direction8::DownLeft);
+        assert_eq!("<".parse::<Direction8>().unwrap(), Direction8::Left);
+        for direction in Direction8::iter() {
+            assert_eq!(
+                direction.to_string().parse::<Direction8>().unwrap(),
+                direction
+            );
+        }
+        for direction in Direction::iter() {
+            assert_eq!(Direction::try_from(direction.to_char()).unwrap(), direction);
+        }
+    }
+
+    #[test]
+    fn conversion() {
+        for direction in Direction::iter() {
+            assert_eq!(
+                Direction::try_from(Direction8::from(direction)).unwrap(),
+                direction
+            );
+        }
+        assert_eq!(
+            Direction::try_from(Direction8::DownLeft),
+            Err(NotOrthogonalError(Direction8::DownLeft))
+        );
+    }
+
+    #[test]
+    fn point_arithmetic() {
+        let mut p: Point<i64> = Point::new(2, 2);
+        p += Direction::Up;
+        p -= Direction8::DownRight;
+        assert_eq!(p, Point::new(1, 0));
+        assert_eq!(p + Direction::Left - Direction::Right, Point::new(-1, 0));
+    }
+}
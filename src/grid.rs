@@ -0,0 +1,617 @@
+//! A dense 2D grid, the starting point for most map and maze puzzles
+//!
+//! Cells are stored row by row and addressed with [`Point`]s, where `x` is the column and `y` is the row.
+//! Iteration, [`Grid::find`] and friends visit the cells in reading order.
+//!
+//! ```
+//! use advent_of_code::grid::Grid;
+//! use advent_of_code::point::Point;
+//!
+//! let grid: Grid<char> = "#.#\n..S".parse().unwrap();
+//! assert_eq!((grid.width(), grid.height()), (3, 2));
+//!
+//! let start = grid.find(&'S').unwrap();
+//! assert_eq!(start, Point::new(2, 1));
+//! assert_eq!(grid.neighbors4(start).count(), 2);
+//!
+//! let digits = Grid::parse_with("12\n34", |c| c.to_digit(10).unwrap()).unwrap();
+//! assert_eq!(digits[Point::new(1, 1)], 4);
+//! assert_eq!(digits.transpose().row(0), [1, 3]);
+//! ```
+
+use std::error::Error;
+use std::fmt::Display;
+use std::ops::{Index, IndexMut};
+use std::str::FromStr;
+
+use crate::point::Point;
+
+#[derive(PartialEq, Eq, Hash, Clone, Debug)]
+pub struct Grid<T> {
+    cells: Vec<T>,
+    width: usize,
+    height: usize,
+}
+
+impl<T> Grid<T> {
+    /// Creates a grid filled with copies of `value`.
+    pub fn new(width: usize, height: usize, value: T) -> Self
+    where
+        T: Clone,
+    {
+        Grid {
+            cells: vec![value; width * height],
+            width,
+            height,
+        }
+    }
+
+    /// Creates a grid by calling `func` with the position of every cell, in reading order.
+    pub fn from_fn(width: usize, height: usize, mut func: impl FnMut(Point) -> T) -> Self {
+        let cells = (0..height)
+            .flat_map(|y| (0..width).map(move |x| to_point(x, y)))
+            .map(&mut func)
+            .collect();
+        Grid {
+            cells,
+            width,
+            height,
+        }
+    }
+
+    /// Creates a grid from cells in reading order.
+    /// Returns `None` if the number of cells is not a multiple of `width`.
+    pub fn from_vec(width: usize, cells: Vec<T>) -> Option<Self> {
+        if width == 0 {
+            return cells.is_empty().then_some(Grid {
+                cells,
+                width: 0,
+                height: 0,
+            });
+        }
+        cells.len().is_multiple_of(width).then(|| Grid {
+            height: cells.len() / width,
+            cells,
+            width,
+        })
+    }
+
+    /// Parses one cell per character, one row per line. Trailing empty lines are ignored.
+    pub fn parse_with(input: &str, mut func: impl FnMut(char) -> T) -> Result<Self, GridError> {
+        Self::try_parse_with(input, |c| Some(func(c)))
+    }
+
+    /// Like [`Grid::parse_with`], but fails with [`GridError::InvalidCell`] if `func` returns `None`.
+    pub fn try_parse_with(
+        input: &str,
+        mut func: impl FnMut(char) -> Option<T>,
+    ) -> Result<Self, GridError> {
+        let mut cells = vec![];
+        let mut width = None;
+        let mut height = 0;
+
+        for (line_idx, line) in input.trim_end_matches(['\r', '\n']).lines().enumerate() {
+            let before = cells.len();
+
+            for (column, c) in line.chars().enumerate() {
+                let cell = func(c).ok_or(GridError::InvalidCell {
+                    line: line_idx + 1,
+                    column: column + 1,
+                    cell: c,
+                })?;
+                cells.push(cell);
+            }
+
+            let row_width = cells.len() - before;
+            match width {
+                None => width = Some(row_width),
+                Some(expected) if expected != row_width => {
+                    return Err(GridError::RaggedRow {
+                        line: line_idx + 1,
+                        expected,
+                        found: row_width,
+                    });
+                }
+                Some(_) => {}
+            }
+            height += 1;
+        }
+
+        Ok(Grid {
+            cells,
+            width: width.unwrap_or(0),
+            height,
+        })
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    /// Checks if `point` lies within the grid
+    pub fn contains(&self, point: Point) -> bool {
+        self.index_of(point).is_some()
+    }
+
+    pub fn get(&self, point: Point) -> Option<&T> {
+        self.index_of(point).map(|idx| &self.cells[idx])
+    }
+
+    pub fn get_mut(&mut self, point: Point) -> Option<&mut T> {
+        self.index_of(point).map(|idx| &mut self.cells[idx])
+    }
+
+    /// The orthogonal neighbours of `point` that lie within the grid
+    pub fn neighbors4(&self, point: Point) -> impl Iterator<Item = Point> + '_ {
+        point.neighbors4().filter(|&p| self.contains(p))
+    }
+
+    /// The orthogonal and diagonal neighbours of `point` that lie within the grid
+    pub fn neighbors8(&self, point: Point) -> impl Iterator<Item = Point> + '_ {
+        point.neighbors8().filter(|&p| self.contains(p))
+    }
+
+    /// The cells of row `y`
+    ///
+    /// # Panics
+    /// Panics if `y` is out of bounds.
+    pub fn row(&self, y: usize) -> &[T] {
+        assert!(y < self.height, "row {y} is out of bounds");
+        &self.cells[y * self.width..(y + 1) * self.width]
+    }
+
+    /// The cells of row `y`
+    ///
+    /// # Panics
+    /// Panics if `y` is out of bounds.
+    pub fn row_mut(&mut self, y: usize) -> &mut [T] {
+        assert!(y < self.height, "row {y} is out of bounds");
+        &mut self.cells[y * self.width..(y + 1) * self.width]
+    }
+
+    /// The cells of column `x`, top to bottom
+    ///
+    /// # Panics
+    /// Panics if `x` is out of bounds.
+    pub fn column(&self, x: usize) -> impl DoubleEndedIterator<Item = &T> {
+        assert!(x < self.width, "column {x} is out of bounds");
+        self.cells.iter().skip(x).step_by(self.width)
+    }
+
+    pub fn rows(&self) -> impl DoubleEndedIterator<Item = &[T]> {
+        // `chunks` panics on a chunk size of zero, an empty grid has no rows either way.
+        self.cells.chunks(self.width.max(1))
+    }
+
+    pub fn columns(&self) -> impl Iterator<Item = impl DoubleEndedIterator<Item = &T>> {
+        (0..self.width).map(|x| self.column(x))
+    }
+
+    /// All positions of the grid in reading order
+    pub fn points(&self) -> impl Iterator<Item = Point> {
+        let width = self.width;
+        (0..self.height).flat_map(move |y| (0..width).map(move |x| to_point(x, y)))
+    }
+
+    /// All cells together with their position, in reading order
+    pub fn iter(&self) -> impl Iterator<Item = (Point, &T)> {
+        self.points().zip(&self.cells)
+    }
+
+    pub fn iter_mut(&mut self) -> impl Iterator<Item = (Point, &mut T)> {
+        self.points().zip(&mut self.cells)
+    }
+
+    /// The first position of `value` in reading order
+    pub fn find(&self, value: &T) -> Option<Point>
+    where
+        T: PartialEq,
+    {
+        self.position(|cell| cell == value)
+    }
+
+    /// All positions of `value` in reading order
+    pub fn find_all<'a>(&'a self, value: &'a T) -> impl Iterator<Item = Point> + 'a
+    where
+        T: PartialEq,
+    {
+        self.positions(move |cell| cell == value)
+    }
+
+    /// The first position whose cell matches `predicate`, in reading order
+    pub fn position(&self, mut predicate: impl FnMut(&T) -> bool) -> Option<Point> {
+        self.iter()
+            .find(|(_, cell)| predicate(cell))
+            .map(|(point, _)| point)
+    }
+
+    /// All positions whose cells match `predicate`, in reading order
+    pub fn positions<'a>(
+        &'a self,
+        mut predicate: impl FnMut(&T) -> bool + 'a,
+    ) -> impl Iterator<Item = Point> + 'a {
+        self.iter()
+            .filter(move |(_, cell)| predicate(cell))
+            .map(|(point, _)| point)
+    }
+
+    /// Creates a grid of the same size by applying `func` to every cell.
+    pub fn map<U>(&self, func: impl FnMut(&T) -> U) -> Grid<U> {
+        Grid {
+            cells: self.cells.iter().map(func).collect(),
+            width: self.width,
+            height: self.height,
+        }
+    }
+
+    /// Mirrors the grid along its main diagonal, i.e. rows become columns.
+    #[must_use]
+    pub fn transpose(&self) -> Self
+    where
+        T: Clone,
+    {
+        self.transformed(self.height, self.width, |x, y| (y, x))
+    }
+
+    /// Rotates the grid by 90° counter-clockwise.
+    #[must_use]
+    pub fn rotate_left(&self) -> Self
+    where
+        T: Clone,
+    {
+        self.transformed(self.height, self.width, |x, y| (self.width - 1 - y, x))
+    }
+
+    /// Rotates the grid by 90° clockwise.
+    #[must_use]
+    pub fn rotate_right(&self) -> Self
+    where
+        T: Clone,
+    {
+        self.transformed(self.height, self.width, |x, y| (y, self.height - 1 - x))
+    }
+
+    /// Mirrors the grid left to right.
+    #[must_use]
+    pub fn flip_horizontal(&self) -> Self
+    where
+        T: Clone,
+    {
+        self.transformed(self.width, self.height, |x, y| (self.width - 1 - x, y))
+    }
+
+    /// Mirrors the grid top to bottom.
+    #[must_use]
+    pub fn flip_vertical(&self) -> Self
+    where
+        T: Clone,
+    {
+        self.transformed(self.width, self.height, |x, y| (x, self.height - 1 - y))
+    }
+
+    /// Creates a `width` x `height` grid whose cell at `(x, y)` is the cell at `source(x, y)` of this grid.
+    fn transformed(
+        &self,
+        width: usize,
+        height: usize,
+        source: impl Fn(usize, usize) -> (usize, usize),
+    ) -> Self
+    where
+        T: Clone,
+    {
+        let cells = (0..height)
+            .flat_map(|y| (0..width).map(move |x| (x, y)))
+            .map(|(x, y)| {
+                let (source_x, source_y) = source(x, y);
+                self.cells[source_y * self.width + source_x].clone()
+            })
+            .collect();
+        Grid {
+            cells,
+            width,
+            height,
+        }
+    }
+
+    /// An infinite view that repeats the grid in every direction.
+    pub fn tiled(&self) -> Tiled<'_, T> {
+        Tiled { grid: self }
+    }
+
+    fn index_of(&self, point: Point) -> Option<usize> {
+        point
+            .index_in(self.width, self.height)
+            .map(|(x, y)| y * self.width + x)
+    }
+}
+
+fn to_point(x: usize, y: usize) -> Point {
+    Point::try_from((x, y)).expect("grid dimensions should fit into i32")
+}
+
+/// # Panics
+/// Panics if `point` is out of bounds, use [`Grid::get`] for a checked lookup.
+impl<T> Index<Point> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, point: Point) -> &T {
+        self.get(point)
+            .unwrap_or_else(|| panic!("point {point} is out of bounds"))
+    }
+}
+
+/// # Panics
+/// Panics if `point` is out of bounds, use [`Grid::get_mut`] for a checked lookup.
+impl<T> IndexMut<Point> for Grid<T> {
+    fn index_mut(&mut self, point: Point) -> &mut T {
+        self.get_mut(point)
+            .unwrap_or_else(|| panic!("point {point} is out of bounds"))
+    }
+}
+
+impl FromStr for Grid<char> {
+    type Err = GridError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Grid::parse_with(s, |c| c)
+    }
+}
+
+/// Renders one line per row, without a trailing newline.
+impl<T: Display> Display for Grid<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for (y, row) in self.rows().enumerate() {
+            if y > 0 {
+                writeln!(f)?;
+            }
+            for cell in row {
+                write!(f, "{cell}")?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+/// An infinite view of a [`Grid`] that repeats it in every direction, see [`Grid::tiled`].
+///
+/// ```
+/// use advent_of_code::grid::Grid;
+/// use advent_of_code::point::Point;
+///
+/// let grid: Grid<char> = "ab\ncd".parse().unwrap();
+/// let tiled = grid.tiled();
+/// assert_eq!(tiled[Point::new(-1, 5)], 'd');
+/// assert_eq!(tiled.tile_of(Point::new(-1, 5)), Point::new(-1, 2));
+/// ```
+#[derive(Clone, Copy, Debug)]
+pub struct Tiled<'a, T> {
+    grid: &'a Grid<T>,
+}
+
+impl<'a, T> Tiled<'a, T> {
+    /// The position within the base grid that `point` repeats
+    ///
+    /// # Panics
+    /// Panics if the base grid is empty.
+    pub fn wrap(&self, point: Point) -> Point {
+        let (width, height) = self.size();
+        Point::new(point.x.rem_euclid(width), point.y.rem_euclid(height))
+    }
+
+    /// The copy of the base grid that `point` lies in, the base grid itself is tile `(0, 0)`
+    ///
+    /// # Panics
+    /// Panics if the base grid is empty.
+    pub fn tile_of(&self, point: Point) -> Point {
+        let (width, height) = self.size();
+        Point::new(point.x.div_euclid(width), point.y.div_euclid(height))
+    }
+
+    /// The cell at `point`, wrapped into the base grid
+    ///
+    /// # Panics
+    /// Panics if the base grid is empty.
+    pub fn get(&self, point: Point) -> &'a T {
+        &self.grid[self.wrap(point)]
+    }
+
+    /// Renders the area between `min` and `max` (inclusive), one line per row.
+    pub fn render(&self, min: Point, max: Point) -> String
+    where
+        T: Display,
+    {
+        (min.y..=max.y)
+            .map(|y| {
+                (min.x..=max.x)
+                    .map(|x| self.get(Point::new(x, y)).to_string())
+                    .collect::<String>()
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    fn size(&self) -> (i32, i32) {
+        assert!(
+            self.grid.width > 0 && self.grid.height > 0,
+            "cannot tile an empty grid"
+        );
+        let Point { x, y } = to_point(self.grid.width, self.grid.height);
+        (x, y)
+    }
+}
+
+impl<T> Index<Point> for Tiled<'_, T> {
+    type Output = T;
+
+    fn index(&self, point: Point) -> &T {
+        self.get(point)
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+/// An error which can be returned when parsing a [`Grid`].
+#[derive(Debug, PartialEq, Eq)]
+pub enum GridError {
+    RaggedRow {
+        line: usize,
+        expected: usize,
+        found: usize,
+    },
+    InvalidCell {
+        line: usize,
+        column: usize,
+        cell: char,
+    },
+}
+
+impl Error for GridError {}
+
+impl Display for GridError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            GridError::RaggedRow {
+                line,
+                expected,
+                found,
+            } => write!(
+                f,
+                "line {line} has {found} cells, expecting {expected} like the first line"
+            ),
+            GridError::InvalidCell { line, column, cell } => {
+                write!(f, "invalid cell {cell:?} at line {line}, column {column}")
+            }
+        }
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::{Grid, GridError};
+    use crate::point::Point;
+
+    const INPUT: &str = "abc\ndef\n";
+
+    fn grid() -> Grid<char> {
+        INPUT.parse().unwrap()
+    }
+
+    #[test]
+    fn parses_and_displays() {
+        let grid = grid();
+        assert_eq!((grid.width(), grid.height()), (3, 2));
+        assert_eq!(grid[Point::new(1, 1)], 'e');
+        assert_eq!(grid.to_string(), INPUT.trim_end());
+
+        let empty: Grid<char> = "".parse().unwrap();
+        assert_eq!((empty.width(), empty.height()), (0, 0));
+        assert_eq!(empty.rows().count(), 0);
+    }
+
+    #[test]
+    fn parse_errors() {
+        assert_eq!(
+            "ab\nabc".parse::<Grid<char>>(),
+            Err(GridError::RaggedRow {
+                line: 2,
+                expected: 2,
+                found: 3
+            })
+        );
+        assert_eq!(
+            Grid::try_parse_with("12\n3x", |c| c.to_digit(10)),
+            Err(GridError::InvalidCell {
+                line: 2,
+                column: 2,
+                cell: 'x'
+            })
+        );
+    }
+
+    #[test]
+    fn bounds() {
+        let mut grid = grid();
+        assert!(grid.contains(Point::new(2, 1)));
+        assert!(!grid.contains(Point::new(3, 1)));
+        assert!(!grid.contains(Point::new(0, -1)));
+        assert_eq!(grid.get(Point::new(0, 2)), None);
+        *grid.get_mut(Point::new(0, 0)).unwrap() = 'z';
+        grid[Point::new(2, 1)] = 'y';
+        assert_eq!(grid.to_string(), "zbc\ndey");
+    }
+
+    #[test]
+    fn neighbors() {
+        let grid = grid();
+        let corner: Vec<Point> = grid.neighbors4(Point::new(0, 0)).collect();
+        assert_eq!(corner, [Point::new(1, 0), Point::new(0, 1)]);
+        assert_eq!(grid.neighbors8(Point::new(1, 0)).count(), 5);
+    }
+
+    #[test]
+    fn rows_and_columns() {
+        let grid = grid();
+        assert_eq!(grid.row(1), ['d', 'e', 'f']);
+        assert_eq!(grid.column(2).collect::<String>(), "cf");
+        assert_eq!(grid.rows().count(), 2);
+        let columns: Vec<String> = grid.columns().map(|c| c.collect()).collect();
+        assert_eq!(columns, ["ad", "be", "cf"]);
+    }
+
+    #[test]
+    fn finding() {
+        let grid: Grid<char> = "a.a\n.a.".parse().unwrap();
+        assert_eq!(grid.find(&'a'), Some(Point::new(0, 0)));
+        assert_eq!(grid.find(&'x'), None);
+        let all: Vec<Point> = grid.find_all(&'a').collect();
+        assert_eq!(all, [Point::new(0, 0), Point::new(2, 0), Point::new(1, 1)]);
+        assert_eq!(grid.positions(|&c| c == '.').count(), 3);
+    }
+
+    #[test]
+    fn transformations() {
+        let grid = grid();
+        assert_eq!(grid.transpose().to_string(), "ad\nbe\ncf");
+        assert_eq!(grid.rotate_right().to_string(), "da\neb\nfc");
+        assert_eq!(grid.rotate_left().to_string(), "cf\nbe\nad");
+        assert_eq!(grid.flip_horizontal().to_string(), "cba\nfed");
+        assert_eq!(grid.flip_vertical().to_string(), "def\nabc");
+        assert_eq!(grid.rotate_left().rotate_right(), grid);
+        assert_eq!(grid.transpose().transpose(), grid);
+        assert_eq!(
+            grid.rotate_right().rotate_right(),
+            grid.flip_horizontal().flip_vertical()
+        );
+    }
+
+    #[test]
+    fn tiling() {
+        let grid = grid();
+        let tiled = grid.tiled();
+        assert_eq!(tiled[Point::new(4, 3)], 'e');
+        assert_eq!(tiled.wrap(Point::new(-1, -1)), Point::new(2, 1));
+        assert_eq!(tiled.tile_of(Point::new(-1, -1)), Point::new(-1, -1));
+        assert_eq!(tiled.tile_of(Point::new(5, 1)), Point::new(1, 0));
+        assert_eq!(
+            tiled.render(Point::new(-1, 0), Point::new(3, 2)),
+            "cabca\nfdefd\ncabca"
+        );
+    }
+
+    #[test]
+    fn construction() {
+        let grid = Grid::from_fn(3, 2, |p| p.x + p.y * 10);
+        assert_eq!(grid.row(1), [10, 11, 12]);
+        assert_eq!(Grid::from_vec(3, vec![0, 1, 2, 10, 11, 12]), Some(grid));
+        assert_eq!(Grid::from_vec(4, vec![0; 6]), None);
+        assert_eq!(Grid::new(2, 2, 0).map(|c| c + 1).to_string(), "11\n11");
+    }
+}
@@ -2,5 +2,16 @@ pub use day::*;
 
 mod day;
 
+pub mod cycle;
+pub mod direction;
+pub mod grid;
+pub mod matcher;
+pub mod math;
+pub mod parse;
 pub mod point;
+pub mod ranges;
+pub mod search;
+pub mod sequence;
+pub mod sparse_grid;
 pub mod template;
+pub mod testing;
@@ -4,6 +4,7 @@ use args::{parse, AppArguments};
 mod args {
     use std::process;
 
+    use advent_of_code::template::report;
     use advent_of_code::Day;
 
     pub enum AppArguments {
@@ -20,22 +21,44 @@ mod args {
             day: Day,
             release: bool,
             time: bool,
+            mem: bool,
+            profile: bool,
             submit: Option<u8>,
+            options: Vec<String>,
         },
         All {
             release: bool,
             time: bool,
+            mem: bool,
+            dry_run: bool,
+            report: Option<report::Target>,
         },
     }
 
     pub fn parse() -> Result<AppArguments, Box<dyn std::error::Error>> {
         let mut args = pico_args::Arguments::from_env();
 
-        let app_args = match args.subcommand()?.as_deref() {
-            Some("all") => AppArguments::All {
-                release: args.contains("--release"),
-                time: args.contains("--time"),
-            },
+        let mut app_args = match args.subcommand()?.as_deref() {
+            Some("all") => {
+                let report_path: Option<String> = args.opt_value_from_str("--report")?;
+                let report_format: Option<report::Format> = args.opt_value_from_str("--format")?;
+
+                if report_format.is_some() && report_path.is_none() {
+                    eprintln!("The --format option requires --report.");
+                    process::exit(1);
+                }
+
+                AppArguments::All {
+                    release: args.contains("--release"),
+                    time: args.contains("--time"),
+                    mem: args.contains("--mem"),
+                    dry_run: args.contains("--dry-run"),
+                    report: report_path.map(|path| report::Target {
+                        format: report_format.unwrap_or_else(|| report::Format::from_path(&path)),
+                        path,
+                    }),
+                }
+            }
             Some("download") => AppArguments::Download {
                 day: args.free_from_str()?,
             },
@@ -50,6 +73,9 @@ mod args {
                 release: args.contains("--release"),
                 submit: args.opt_value_from_str("--submit")?,
                 time: args.contains("--time"),
+                mem: args.contains("--mem"),
+                profile: args.contains("--profile"),
+                options: vec![],
             },
             Some(x) => {
                 eprintln!("Unknown command: {x}");
@@ -62,7 +88,10 @@ mod args {
         };
 
         let remaining = args.finish();
-        if !remaining.is_empty() {
+        if let AppArguments::Solve { options, .. } = &mut app_args {
+            // everything else is meant for the solution itself.
+            options.extend(remaining.iter().map(|x| x.to_string_lossy().into_owned()));
+        } else if !remaining.is_empty() {
             eprintln!("Warning: unknown argument(s): {remaining:?}.");
         }
 
@@ -77,7 +106,13 @@ fn main() {
             std::process::exit(1);
         }
         Ok(args) => match args {
-            AppArguments::All { release, time } => all::handle(release, time),
+            AppArguments::All {
+                release,
+                time,
+                mem,
+                dry_run,
+                report,
+            } => all::handle(release, time, mem, dry_run, report),
             AppArguments::Download { day } => download::handle(day),
             AppArguments::Read { day } => read::handle(day),
             AppArguments::Scaffold { day } => scaffold::handle(day),
@@ -85,8 +120,11 @@ fn main() {
                 day,
                 release,
                 time,
+                mem,
+                profile,
                 submit,
-            } => solve::handle(day, release, time, submit),
+                options,
+            } => solve::handle(day, release, time, mem, profile, submit, &options),
         },
     };
 }
@@ -0,0 +1,216 @@
+//! Searching a text for many patterns at once
+//!
+//! [`Matcher`] is an Aho-Corasick automaton: the patterns are stored in a trie, and every node knows the
+//! longest suffix of its prefix that is also a prefix in the trie. A search reads every byte of the text once,
+//! no matter how many patterns there are, and reports all matches, including the ones that overlap.
+//!
+//! ```
+//! use advent_of_code::matcher::Matcher;
+//!
+//! let matcher = Matcher::new([("one", 1), ("two", 2), ("2", 2)]);
+//! let matches: Vec<(usize, usize, i32)> = matcher
+//!     .find_overlapping("xtwone2")
+//!     .map(|m| (m.start, m.end, *m.value))
+//!     .collect();
+//! assert_eq!(matches, [(1, 4, 2), (3, 6, 1), (6, 7, 2)]);
+//! ```
+
+/// A set of patterns with a value each, ready to be searched for.
+#[derive(Clone, Debug)]
+pub struct Matcher<V> {
+    nodes: Vec<Node>,
+    /// The length in bytes and the value of every pattern
+    patterns: Vec<(usize, V)>,
+}
+
+#[derive(Clone, Debug, Default)]
+struct Node {
+    /// The children of the node, sorted by byte
+    next: Vec<(u8, usize)>,
+    /// The node of the longest proper suffix that is also in the trie
+    fail: usize,
+    /// The patterns that end at this node, longest first, including those that are suffixes of it
+    outputs: Vec<usize>,
+}
+
+/// An occurrence of a pattern in the text, `start..end` are byte offsets.
+#[derive(PartialEq, Eq, Hash, Clone, Copy, Debug)]
+pub struct Match<'a, V> {
+    pub start: usize,
+    pub end: usize,
+    pub value: &'a V,
+}
+
+const ROOT: usize = 0;
+
+impl<V> Matcher<V> {
+    /// Builds the automaton. Empty patterns never match.
+    pub fn new<P: AsRef<str>>(patterns: impl IntoIterator<Item = (P, V)>) -> Self {
+        let mut matcher = Matcher {
+            nodes: vec![Node::default()],
+            patterns: vec![],
+        };
+
+        for (pattern, value) in patterns {
+            let pattern = pattern.as_ref();
+            if pattern.is_empty() {
+                continue;
+            }
+            let node = pattern
+                .bytes()
+                .fold(ROOT, |node, byte| matcher.insert(node, byte));
+            matcher.nodes[node].outputs.push(matcher.patterns.len());
+            matcher.patterns.push((pattern.len(), value));
+        }
+
+        matcher.link();
+        matcher
+    }
+
+    /// The child of `node` for `byte`, created if it does not exist yet.
+    fn insert(&mut self, node: usize, byte: u8) -> usize {
+        match self.child(node, byte) {
+            Some(child) => child,
+            None => {
+                let child = self.nodes.len();
+                self.nodes.push(Node::default());
+                let next = &mut self.nodes[node].next;
+                let idx = next.partition_point(|&(b, _)| b < byte);
+                next.insert(idx, (byte, child));
+                child
+            }
+        }
+    }
+
+    fn child(&self, node: usize, byte: u8) -> Option<usize> {
+        let next = &self.nodes[node].next;
+        next.binary_search_by_key(&byte, |&(b, _)| b)
+            .ok()
+            .map(|idx| next[idx].1)
+    }
+
+    /// Sets the suffix link of every node, parents before children, and collects the patterns of the suffixes.
+    fn link(&mut self) {
+        let mut queue = std::collections::VecDeque::from([ROOT]);
+        while let Some(node) = queue.pop_front() {
+            for idx in 0..self.nodes[node].next.len() {
+                let (byte, child) = self.nodes[node].next[idx];
+                let fail = if node == ROOT {
+                    ROOT
+                } else {
+                    self.advance(self.nodes[node].fail, byte)
+                };
+
+                let inherited = self.nodes[fail].outputs.clone();
+                self.nodes[child].fail = fail;
+                self.nodes[child].outputs.extend(inherited);
+                queue.push_back(child);
+            }
+        }
+    }
+
+    /// The state after reading `byte` in state `node`
+    fn advance(&self, mut node: usize, byte: u8) -> usize {
+        loop {
+            if let Some(child) = self.child(node, byte) {
+                return child;
+            }
+            if node == ROOT {
+                return ROOT;
+            }
+            node = self.nodes[node].fail;
+        }
+    }
+
+    /// All matches in `haystack`, ordered by their end and the longest first if several end at the same byte.
+    /// Patterns and haystack are valid UTF-8, so the matches always start and end at a char boundary.
+    pub fn find_overlapping<'a>(&'a self, haystack: &'a str) -> impl Iterator<Item = Match<'a, V>> {
+        haystack
+            .bytes()
+            .scan(ROOT, move |node, byte| {
+                *node = self.advance(*node, byte);
+                Some(*node)
+            })
+            .enumerate()
+            .flat_map(move |(idx, node)| {
+                self.nodes[node].outputs.iter().map(move |&pattern| {
+                    let (len, value) = &self.patterns[pattern];
+                    Match {
+                        start: idx + 1 - len,
+                        end: idx + 1,
+                        value,
+                    }
+                })
+            })
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::Matcher;
+    use crate::testing::Rng;
+
+    fn matches<V: Copy>(matcher: &Matcher<V>, haystack: &str) -> Vec<(usize, usize, V)> {
+        matcher
+            .find_overlapping(haystack)
+            .map(|m| (m.start, m.end, *m.value))
+            .collect()
+    }
+
+    #[test]
+    fn finds_overlapping_matches() {
+        let matcher = Matcher::new([("he", 0), ("she", 1), ("his", 2), ("hers", 3)]);
+        assert_eq!(
+            matches(&matcher, "ushers"),
+            [(1, 4, 1), (2, 4, 0), (2, 6, 3)]
+        );
+        assert_eq!(matches(&matcher, "ahishers").len(), 4);
+        assert!(matches(&matcher, "").is_empty());
+
+        let matcher = Matcher::new([("", 0), ("aa", 1)]);
+        assert_eq!(matches(&matcher, "aaa"), [(0, 2, 1), (1, 3, 1)]);
+    }
+
+    #[test]
+    fn matches_unicode_patterns() {
+        let matcher = Matcher::new([("eins", 1), ("zwei", 2), ("drei", 3), ("fünf", 5)]);
+        let found = matches(&matcher, "zweifünfeins");
+        assert_eq!(found, [(0, 4, 2), (4, 9, 5), (9, 13, 1)]);
+        assert_eq!(&"zweifünfeins"[4..9], "fünf");
+    }
+
+    #[test]
+    fn matches_naive_search() {
+        let mut rng = Rng::new(0x6a09_e667_f3bc_c908);
+        let word = |rng: &mut Rng, len: u64| -> String {
+            let len = 1 + rng.below(len);
+            (0..len)
+                .map(|_| (b'a' + rng.below(3) as u8) as char)
+                .collect()
+        };
+
+        for _ in 0..200 {
+            let patterns: Vec<String> = (0..1 + rng.below(6)).map(|_| word(&mut rng, 4)).collect();
+            let haystack = word(&mut rng, 30);
+            let matcher = Matcher::new(patterns.iter().zip(0..));
+
+            let mut expected = vec![];
+            for end in 1..=haystack.len() {
+                let mut ending: Vec<(usize, usize, usize)> = patterns
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, pattern)| haystack[..end].ends_with(pattern.as_str()))
+                    .map(|(idx, pattern)| (end - pattern.len(), end, idx))
+                    .collect();
+                ending.sort_unstable();
+                expected.extend(ending);
+            }
+
+            let mut found = matches(&matcher, &haystack);
+            found.sort_unstable_by_key(|&(start, end, idx)| (end, start, idx));
+            assert_eq!(found, expected, "{patterns:?} in {haystack}");
+        }
+    }
+}
@@ -0,0 +1,409 @@
+//! Number theory helpers that keep showing up in puzzles about periods, clocks and residues
+//!
+//! Everything is generic over the primitive integer types via [`Integer`].
+//! Functions that can overflow return `None` instead of wrapping around,
+//! and modular arithmetic never overflows, even for moduli close to the maximum of the type.
+//!
+//! ```
+//! use advent_of_code::math;
+//!
+//! assert_eq!(math::lcm_all([4_u64, 6, 10]), Some(60));
+//! assert_eq!(math::lcm(u64::MAX, 2), None);
+//!
+//! // x ≡ 2 (mod 3), x ≡ 3 (mod 5), x ≡ 2 (mod 7)
+//! assert_eq!(math::crt([(2_i64, 3), (3, 5), (2, 7)]), Some((23, 105)));
+//! assert_eq!(math::mod_pow(3_i64, 200, 1_000_000_007), 136_318_165);
+//! assert_eq!(math::isqrt(99_u32), 9);
+//! ```
+
+use std::fmt::{Debug, Display};
+use std::hash::Hash;
+use std::ops::{Add, Div, Mul, Neg, Rem, Sub};
+
+/// The primitive integer types.
+pub trait Integer:
+    Copy
+    + Ord
+    + Hash
+    + Debug
+    + Display
+    + Add<Output = Self>
+    + Sub<Output = Self>
+    + Mul<Output = Self>
+    + Div<Output = Self>
+    + Rem<Output = Self>
+{
+    const ZERO: Self;
+    const ONE: Self;
+    const TWO: Self;
+
+    fn checked_add(self, other: Self) -> Option<Self>;
+    fn checked_sub(self, other: Self) -> Option<Self>;
+    fn checked_mul(self, other: Self) -> Option<Self>;
+
+    /// The absolute value, `None` if it does not fit into the type (i.e. for `MIN` of a signed type)
+    fn checked_abs(self) -> Option<Self>;
+
+    /// The floor of the square root, `None` for negative numbers
+    fn checked_isqrt(self) -> Option<Self>;
+
+    /// The remainder of `self / modulus` in the range `0..modulus`
+    fn rem_euclid(self, modulus: Self) -> Self;
+
+    /// `self * other` modulo `modulus` without overflowing
+    fn mul_mod(self, other: Self, modulus: Self) -> Self;
+}
+
+/// The signed primitive integer types. Required for functions with negative intermediate results.
+pub trait SignedInteger: Integer + Neg<Output = Self> {}
+
+macro_rules! impl_integer {
+    ($($int:ty => |$a:ident, $b:ident, $m:ident| $mul_mod:expr, abs: |$x:ident| $abs:expr, isqrt: |$y:ident| $isqrt:expr;)*) => {
+        $(
+            impl Integer for $int {
+                const ZERO: Self = 0;
+                const ONE: Self = 1;
+                const TWO: Self = 2;
+
+                fn checked_add(self, other: Self) -> Option<Self> {
+                    <$int>::checked_add(self, other)
+                }
+
+                fn checked_sub(self, other: Self) -> Option<Self> {
+                    <$int>::checked_sub(self, other)
+                }
+
+                fn checked_mul(self, other: Self) -> Option<Self> {
+                    <$int>::checked_mul(self, other)
+                }
+
+                fn checked_abs(self) -> Option<Self> {
+                    let $x = self;
+                    $abs
+                }
+
+                fn checked_isqrt(self) -> Option<Self> {
+                    let $y = self;
+                    $isqrt
+                }
+
+                fn rem_euclid(self, modulus: Self) -> Self {
+                    <$int>::rem_euclid(self, modulus)
+                }
+
+                fn mul_mod(self, other: Self, modulus: Self) -> Self {
+                    let ($a, $b, $m) = (self.rem_euclid(modulus), other.rem_euclid(modulus), modulus);
+                    $mul_mod
+                }
+            }
+        )*
+    };
+}
+
+// types up to 64 bits multiply in 128 bits, the 128 bit types fall back to double-and-add.
+impl_integer! {
+    i8 => |a, b, m| (i128::from(a) * i128::from(b) % i128::from(m)) as i8, abs: |x| x.checked_abs(), isqrt: |x| x.checked_isqrt();
+    i16 => |a, b, m| (i128::from(a) * i128::from(b) % i128::from(m)) as i16, abs: |x| x.checked_abs(), isqrt: |x| x.checked_isqrt();
+    i32 => |a, b, m| (i128::from(a) * i128::from(b) % i128::from(m)) as i32, abs: |x| x.checked_abs(), isqrt: |x| x.checked_isqrt();
+    i64 => |a, b, m| (i128::from(a) * i128::from(b) % i128::from(m)) as i64, abs: |x| x.checked_abs(), isqrt: |x| x.checked_isqrt();
+    isize => |a, b, m| (a as i128 * b as i128 % m as i128) as isize, abs: |x| x.checked_abs(), isqrt: |x| x.checked_isqrt();
+    i128 => |a, b, m| mul_mod_by_doubling(a, b, m), abs: |x| x.checked_abs(), isqrt: |x| x.checked_isqrt();
+    u8 => |a, b, m| (u128::from(a) * u128::from(b) % u128::from(m)) as u8, abs: |x| Some(x), isqrt: |x| Some(x.isqrt());
+    u16 => |a, b, m| (u128::from(a) * u128::from(b) % u128::from(m)) as u16, abs: |x| Some(x), isqrt: |x| Some(x.isqrt());
+    u32 => |a, b, m| (u128::from(a) * u128::from(b) % u128::from(m)) as u32, abs: |x| Some(x), isqrt: |x| Some(x.isqrt());
+    u64 => |a, b, m| (u128::from(a) * u128::from(b) % u128::from(m)) as u64, abs: |x| Some(x), isqrt: |x| Some(x.isqrt());
+    usize => |a, b, m| (a as u128 * b as u128 % m as u128) as usize, abs: |x| Some(x), isqrt: |x| Some(x.isqrt());
+    u128 => |a, b, m| mul_mod_by_doubling(a, b, m), abs: |x| Some(x), isqrt: |x| Some(x.isqrt());
+}
+
+impl SignedInteger for i8 {}
+impl SignedInteger for i16 {}
+impl SignedInteger for i32 {}
+impl SignedInteger for i64 {}
+impl SignedInteger for isize {}
+impl SignedInteger for i128 {}
+
+/// `a * b` modulo `m` for `a` and `b` in `0..m`, using only additions that cannot overflow.
+fn mul_mod_by_doubling<T: Integer>(mut a: T, mut b: T, m: T) -> T {
+    let add_mod = |x: T, y: T| if x >= m - y { x - (m - y) } else { x + y };
+
+    let mut result = T::ZERO;
+    while b > T::ZERO {
+        if b % T::TWO == T::ONE {
+            result = add_mod(result, a);
+        }
+        a = add_mod(a, a);
+        b = b / T::TWO;
+    }
+    result
+}
+
+/* -------------------------------------------------------------------------- */
+
+/// The greatest common divisor, always non-negative. `gcd(0, 0)` is `0`.
+///
+/// # Panics
+/// Panics if an intermediate result overflows, which only happens for `MIN` of a signed type,
+/// e.g. `gcd(MIN, 0)` or `gcd(MIN, -1)`.
+pub fn gcd<T: Integer>(mut a: T, mut b: T) -> T {
+    while b != T::ZERO {
+        (a, b) = (b, a % b);
+    }
+    a.checked_abs().expect("gcd should fit into the type")
+}
+
+/// The least common multiple, always non-negative. `None` if it overflows.
+pub fn lcm<T: Integer>(a: T, b: T) -> Option<T> {
+    if a == T::ZERO || b == T::ZERO {
+        return Some(T::ZERO);
+    }
+    (a / gcd(a, b)).checked_mul(b)?.checked_abs()
+}
+
+/// The greatest common divisor of all `values`, `0` if there are none.
+pub fn gcd_all<T: Integer>(values: impl IntoIterator<Item = T>) -> T {
+    values.into_iter().fold(T::ZERO, gcd)
+}
+
+/// The least common multiple of all `values`, `1` if there are none. `None` if it overflows.
+pub fn lcm_all<T: Integer>(values: impl IntoIterator<Item = T>) -> Option<T> {
+    values
+        .into_iter()
+        .try_fold(T::ONE, |result, value| lcm(result, value))
+}
+
+/// The extended Euclidean algorithm. Returns `(g, x, y)` with `a * x + b * y == g == gcd(a, b)`.
+/// `None` if an intermediate result overflows, which can only happen close to `MIN`.
+pub fn extended_gcd<T: SignedInteger>(a: T, b: T) -> Option<(T, T, T)> {
+    let (mut old_r, mut r) = (a, b);
+    let (mut old_x, mut x) = (T::ONE, T::ZERO);
+    let (mut old_y, mut y) = (T::ZERO, T::ONE);
+
+    while r != T::ZERO {
+        let quotient = old_r / r;
+        (old_r, r) = (r, old_r.checked_sub(quotient.checked_mul(r)?)?);
+        (old_x, x) = (x, old_x.checked_sub(quotient.checked_mul(x)?)?);
+        (old_y, y) = (y, old_y.checked_sub(quotient.checked_mul(y)?)?);
+    }
+
+    if old_r < T::ZERO {
+        Some((old_r.checked_abs()?, -old_x, -old_y))
+    } else {
+        Some((old_r, old_x, old_y))
+    }
+}
+
+/// The `x` in `0..modulus` with `a * x ≡ 1 (mod modulus)`, `None` if `a` and `modulus` are not coprime.
+pub fn mod_inverse<T: SignedInteger>(a: T, modulus: T) -> Option<T> {
+    if modulus <= T::ZERO {
+        return None;
+    }
+    let (g, x, _) = extended_gcd(a.rem_euclid(modulus), modulus)?;
+    (g == T::ONE).then(|| x.rem_euclid(modulus))
+}
+
+/// `base` to the power of `exp` modulo `modulus`, in the range `0..modulus`.
+///
+/// # Panics
+/// Panics if `modulus` is not positive.
+pub fn mod_pow<T: Integer>(base: T, mut exp: u64, modulus: T) -> T {
+    assert!(modulus > T::ZERO, "modulus should be positive");
+
+    let mut base = base.rem_euclid(modulus);
+    let mut result = T::ONE.rem_euclid(modulus);
+    while exp > 0 {
+        if exp % 2 == 1 {
+            result = result.mul_mod(base, modulus);
+        }
+        base = base.mul_mod(base, modulus);
+        exp /= 2;
+    }
+    result
+}
+
+/// Solves a system of congruences `x ≡ a (mod m)` given as `(a, m)` pairs with the Chinese Remainder Theorem.
+/// The moduli don't need to be coprime.
+///
+/// Returns `(x, lcm)` where `x` is the smallest non-negative solution and all solutions are `x + k * lcm`.
+/// `None` if the congruences contradict each other, a modulus is not positive or the `lcm` of the moduli overflows.
+pub fn crt<T: SignedInteger>(congruences: impl IntoIterator<Item = (T, T)>) -> Option<(T, T)> {
+    congruences
+        .into_iter()
+        .try_fold((T::ZERO, T::ONE), |(x, m), (a, n)| {
+            if n <= T::ZERO {
+                return None;
+            }
+            let a = a.rem_euclid(n);
+
+            // x + m * k ≡ a (mod n) has a solution iff gcd(m, n) divides a - x.
+            let g = gcd(m, n);
+            let diff = a.checked_sub(x)?;
+            if diff % g != T::ZERO {
+                return None;
+            }
+
+            let n_g = n / g;
+            let k = (diff / g).mul_mod(mod_inverse(m / g, n_g)?, n_g);
+            let combined = lcm(m, n)?;
+            let x = x.checked_add(m.mul_mod(k, combined))?.rem_euclid(combined);
+            Some((x, combined))
+        })
+}
+
+/// The floor of the square root.
+///
+/// # Panics
+/// Panics if `n` is negative, use [`checked_isqrt`] to handle that case.
+pub fn isqrt<T: Integer>(n: T) -> T {
+    checked_isqrt(n).expect("square root of a negative number")
+}
+
+/// The floor of the square root, `None` if `n` is negative.
+pub fn checked_isqrt<T: Integer>(n: T) -> Option<T> {
+    n.checked_isqrt()
+}
+
+/* -------------------------------------------------------------------------- */
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::{
+        checked_isqrt, crt, extended_gcd, gcd, gcd_all, isqrt, lcm, lcm_all, mod_inverse, mod_pow,
+    };
+    use crate::testing::Rng;
+
+    const ITERATIONS: usize = 2000;
+
+    #[test]
+    fn gcd_and_lcm() {
+        assert_eq!(gcd(0, 0), 0);
+        assert_eq!(gcd(-12, 18), 6);
+        assert_eq!(gcd(12_u8, 0), 12);
+        assert_eq!(lcm(-4, 6), Some(12));
+        assert_eq!(lcm(0, 6), Some(0));
+        assert_eq!(lcm(u64::MAX, u64::MAX - 1), None);
+        assert_eq!(lcm(i32::MIN, 1), None);
+        assert_eq!(gcd_all([12, 18, 27]), 3);
+        assert_eq!(gcd_all::<u32>([]), 0);
+        assert_eq!(lcm_all::<u32>([]), Some(1));
+        assert_eq!(lcm_all([1_u8, 2, 3, 4, 5, 6, 7]), None);
+    }
+
+    #[test]
+    fn gcd_and_lcm_properties() {
+        let mut rng = Rng::new(0x9e37_79b9_7f4a_7c15);
+        for _ in 0..ITERATIONS {
+            let a = rng.range(-1_000_000, 1_000_000);
+            let b = rng.range(-1_000_000, 1_000_000);
+            let g = gcd(a, b);
+            assert!(g >= 0);
+            if g != 0 {
+                assert_eq!(a % g, 0);
+                assert_eq!(b % g, 0);
+                assert_eq!(gcd(a / g, b / g), 1);
+            }
+            assert_eq!(g * lcm(a, b).unwrap(), (a * b).abs());
+
+            let (g2, x, y) = extended_gcd(a, b).unwrap();
+            assert_eq!(g2, g);
+            assert_eq!(a * x + b * y, g);
+        }
+    }
+
+    #[test]
+    fn modular_inverse() {
+        assert_eq!(mod_inverse(3, 11), Some(4));
+        assert_eq!(mod_inverse(-3, 11), Some(7));
+        assert_eq!(mod_inverse(6, 9), None);
+        assert_eq!(mod_inverse(1, 1), Some(0));
+        assert_eq!(mod_inverse(2, 0), None);
+
+        let mut rng = Rng::new(0x1234_5678_9abc_def1);
+        for _ in 0..ITERATIONS {
+            let m = rng.range(1, 1_000_000_000);
+            let a = rng.range(-1_000_000_000, 1_000_000_000);
+            match mod_inverse(a, m) {
+                Some(inverse) => {
+                    assert!((0..m).contains(&inverse));
+                    assert_eq!((a.rem_euclid(m) * inverse) % m, 1 % m);
+                }
+                None => assert_ne!(gcd(a, m), 1),
+            }
+        }
+    }
+
+    #[test]
+    fn modular_exponentiation() {
+        assert_eq!(mod_pow(2, 10, 1000), 24);
+        assert_eq!(mod_pow(-2, 3, 5), 2);
+        assert_eq!(mod_pow(7, 0, 1), 0);
+        // needs more than 64 bits for the intermediate products.
+        assert_eq!(mod_pow(u64::MAX - 1, 2, u64::MAX), 1);
+        assert_eq!(mod_pow(u128::MAX - 1, 3, u128::MAX), u128::MAX - 1);
+
+        let mut rng = Rng::new(0x0f0f_0f0f_1234_4321);
+        for _ in 0..ITERATIONS {
+            let m = rng.range(1, 10_000);
+            let base = rng.range(-10_000, 10_000);
+            let exp = rng.next_u64() % 50;
+            let naive = (0..exp).fold(1 % m, |acc, _| (acc * base).rem_euclid(m));
+            assert_eq!(mod_pow(base, exp, m), naive);
+            assert_eq!(
+                mod_pow(i128::from(base), exp, i128::from(m)),
+                i128::from(naive)
+            );
+        }
+    }
+
+    #[test]
+    fn chinese_remainder_theorem() {
+        assert_eq!(crt([(2, 3), (3, 5), (2, 7)]), Some((23, 105)));
+        assert_eq!(crt([(1, 4), (3, 6)]), Some((9, 12)));
+        assert_eq!(crt([(1, 4), (2, 6)]), None);
+        assert_eq!(crt([(5, 0)]), None);
+        assert_eq!(crt::<i64>([]), Some((0, 1)));
+        assert_eq!(crt([(-1, 5)]), Some((4, 5)));
+        // moduli near the limits of the type.
+        let p = 2_147_483_647_i64;
+        let q = 2_147_483_629_i64;
+        let (x, m) = crt([(p - 1, p), (q - 2, q)]).unwrap();
+        assert_eq!(m, p * q);
+        assert_eq!((x % p, x % q), (p - 1, q - 2));
+        assert_eq!(crt([(1, i64::MAX), (0, 2)]), None);
+
+        let mut rng = Rng::new(0xdead_beef_cafe_babe);
+        for _ in 0..ITERATIONS {
+            let count = rng.range(1, 3) as usize;
+            let congruences: Vec<(i64, i64)> = (0..count)
+                .map(|_| (rng.range(-20, 20), rng.range(1, 12)))
+                .collect();
+            let modulus = congruences
+                .iter()
+                .fold(1, |acc, &(_, m)| lcm(acc, m).unwrap());
+            let brute_force = (0..modulus).find(|x| {
+                congruences
+                    .iter()
+                    .all(|&(a, m)| x.rem_euclid(m) == a.rem_euclid(m))
+            });
+            assert_eq!(crt(congruences), brute_force.map(|x| (x, modulus)));
+        }
+    }
+
+    #[test]
+    fn integer_square_root() {
+        assert_eq!(isqrt(0_u64), 0);
+        assert_eq!(isqrt(15_i32), 3);
+        assert_eq!(isqrt(16_i32), 4);
+        assert_eq!(isqrt(u64::MAX), u64::from(u32::MAX));
+        assert_eq!(checked_isqrt(-1), None);
+
+        let mut rng = Rng::new(0x5555_aaaa_3333_cccc);
+        for _ in 0..ITERATIONS {
+            let root = rng.next_u64() >> 32;
+            let n = (root * root).saturating_add_signed(rng.range(-1, 1));
+            let r = u128::from(isqrt(n));
+            assert!(r * r <= u128::from(n));
+            assert!((r + 1) * (r + 1) > u128::from(n));
+        }
+    }
+}
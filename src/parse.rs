@@ -0,0 +1,312 @@
+//! Small parsing helpers for the shapes that puzzle inputs come in
+//!
+//! All helpers return a [`ParseError`] instead of panicking. Errors point at the offending text
+//! with a 1-based line and column, relative to the string the helper was given.
+//! Use [`ParseError::located_in`] to turn that into a position within the whole input.
+//!
+//! ```
+//! use advent_of_code::parse;
+//!
+//! assert_eq!(parse::ints::<i32>("x=-3, y=14..20").unwrap(), [-3, 14, 20]);
+//! assert_eq!(parse::lines_of::<u8>("1\n2\n3").unwrap(), [1, 2, 3]);
+//! assert_eq!(parse::key_value("Card 1: 41 48").unwrap(), ("Card 1", "41 48"));
+//! assert_eq!(parse::assignment("AAA = (BBB, CCC)").unwrap(), ("AAA", ["BBB", "CCC"]));
+//!
+//! let error = parse::lines_of::<u8>("1\n2\nthree").unwrap_err();
+//! assert_eq!((error.line, error.column), (3, 1));
+//! ```
+
+use std::error::Error;
+use std::fmt::Display;
+use std::str::FromStr;
+
+/// An error that points at the text that could not be parsed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    /// 1-based line of the offending text
+    pub line: usize,
+    /// 1-based column of the offending text, counted in characters
+    pub column: usize,
+    /// The offending text, empty if the input ended too early
+    pub text: String,
+    /// What was expected instead, e.g. `an integer` or `"|"`
+    pub expected: String,
+}
+
+impl ParseError {
+    /// Creates an error for `fragment`, which must be a slice of `input`.
+    /// The position is computed from where `fragment` starts within `input`.
+    pub fn at(input: &str, fragment: &str, expected: impl Into<String>) -> Self {
+        let (line, column) = position_of(input, fragment);
+        ParseError {
+            line,
+            column,
+            text: fragment.to_string(),
+            expected: expected.into(),
+        }
+    }
+
+    /// Creates an error for a missing token at the end of `input`.
+    pub fn at_end(input: &str, expected: impl Into<String>) -> Self {
+        ParseError::at(input, &input[input.len()..], expected)
+    }
+
+    /// Moves the position of an error that occurred while parsing `fragment`, a slice of `input`,
+    /// so that it is relative to `input` instead.
+    #[must_use]
+    pub fn located_in(mut self, input: &str, fragment: &str) -> Self {
+        let (line, column) = position_of(input, fragment);
+        if self.line == 1 {
+            self.column += column - 1;
+        }
+        self.line += line - 1;
+        self
+    }
+}
+
+impl Error for ParseError {}
+
+impl Display for ParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "line {}, column {}: expected {}",
+            self.line, self.column, self.expected
+        )?;
+        if self.text.is_empty() {
+            write!(f, ", found end of input")
+        } else {
+            write!(f, ", found {:?}", self.text)
+        }
+    }
+}
+
+/// The 1-based line and column at which `fragment` starts within `input`.
+/// Fragments that are not a slice of `input` are placed at the start.
+fn position_of(input: &str, fragment: &str) -> (usize, usize) {
+    let offset = (fragment.as_ptr() as usize).wrapping_sub(input.as_ptr() as usize);
+    if offset > input.len() {
+        return (1, 1);
+    }
+
+    let before = &input[..offset];
+    let line = before.matches('\n').count() + 1;
+    let line_start = before.rfind('\n').map_or(0, |idx| idx + 1);
+    (line, before[line_start..].chars().count() + 1)
+}
+
+/* -------------------------------------------------------------------------- */
+
+/// Extracts all integers from `s`, ignoring everything in between.
+/// A `-` directly in front of a number is a sign, unless it follows a letter or digit (like in `3-5`).
+pub fn ints<T: FromStr>(s: &str) -> Result<Vec<T>, ParseError> {
+    let bytes = s.as_bytes();
+    let mut numbers = vec![];
+    let mut idx = 0;
+
+    while idx < bytes.len() {
+        if !bytes[idx].is_ascii_digit() {
+            idx += 1;
+            continue;
+        }
+
+        let is_negative = idx > 0
+            && bytes[idx - 1] == b'-'
+            && (idx < 2 || !bytes[idx - 2].is_ascii_alphanumeric());
+        let start = if is_negative { idx - 1 } else { idx };
+        while idx < bytes.len() && bytes[idx].is_ascii_digit() {
+            idx += 1;
+        }
+
+        let number = &s[start..idx];
+        numbers.push(
+            number
+                .parse()
+                .map_err(|_| ParseError::at(s, number, "an integer that fits the target type"))?,
+        );
+    }
+
+    Ok(numbers)
+}
+
+/// Parses every line of `input` with [`FromStr`].
+pub fn lines_of<T: FromStr>(input: &str) -> Result<Vec<T>, ParseError> {
+    lines_with(input, parse_as)
+}
+
+/// Parses every line of `input` with `f`. Errors are located in `input` rather than in the line.
+pub fn lines_with<'a, T>(
+    input: &'a str,
+    mut f: impl FnMut(&'a str) -> Result<T, ParseError>,
+) -> Result<Vec<T>, ParseError> {
+    input
+        .lines()
+        .map(|line| f(line).map_err(|err| err.located_in(input, line)))
+        .collect()
+}
+
+/// Parses every whitespace-separated word of `s` with [`FromStr`].
+pub fn words_of<T: FromStr>(s: &str) -> Result<Vec<T>, ParseError> {
+    s.split_whitespace()
+        .map(|word| parse_as(word).map_err(|err| err.located_in(s, word)))
+        .collect()
+}
+
+/// Parses all of `s` with [`FromStr`], naming the target type in the error.
+pub fn parse_as<T: FromStr>(s: &str) -> Result<T, ParseError> {
+    s.parse().map_err(|_| {
+        let name = std::any::type_name::<T>();
+        let name = name.rsplit("::").next().unwrap_or(name);
+        ParseError::at(s, s, format!("`{name}`"))
+    })
+}
+
+/// Splits `input` into blocks that are separated by one or more blank lines.
+pub fn paragraphs(input: &str) -> impl Iterator<Item = &str> {
+    let mut rest = input;
+
+    std::iter::from_fn(move || {
+        // skip blank lines in front of the next paragraph.
+        while let Some((line, tail)) = rest.split_once('\n') {
+            if !line.trim().is_empty() {
+                break;
+            }
+            rest = tail;
+        }
+        if rest.trim().is_empty() {
+            return None;
+        }
+
+        let mut end = 0;
+        for line in rest.split_inclusive('\n') {
+            if line.trim().is_empty() {
+                break;
+            }
+            end += line.len();
+        }
+
+        let (paragraph, tail) = rest.split_at(end);
+        rest = tail;
+        Some(paragraph.trim_end())
+    })
+}
+
+/// Splits a `key: value` line at the first colon. Both parts are trimmed.
+pub fn key_value(line: &str) -> Result<(&str, &str), ParseError> {
+    let (key, value) = line
+        .split_once(':')
+        .ok_or_else(|| ParseError::at(line, line, "a `key: value` pair"))?;
+    Ok((key.trim(), value.trim()))
+}
+
+/// Parses a `name = (a, b, ...)` line with exactly `N` comma-separated values. Parentheses are optional.
+pub fn assignment<const N: usize>(line: &str) -> Result<(&str, [&str; N]), ParseError> {
+    let (name, values) = line
+        .split_once('=')
+        .ok_or_else(|| ParseError::at(line, line, "a `name = (a, b)` assignment"))?;
+
+    let values = values.trim();
+    let values = values
+        .strip_prefix('(')
+        .and_then(|values| values.strip_suffix(')'))
+        .unwrap_or(values);
+
+    let values: Vec<&str> = values.split(',').map(str::trim).collect();
+    let values: [&str; N] = values.try_into().map_err(|values: Vec<&str>| {
+        let found = values.last().copied().unwrap_or(line);
+        ParseError::at(line, found, format!("{N} comma-separated values"))
+    })?;
+
+    Ok((name.trim(), values))
+}
+
+/// Strips `prefix` from `s`, failing if `s` does not start with it.
+pub fn expect_prefix<'a>(s: &'a str, prefix: &str) -> Result<&'a str, ParseError> {
+    s.strip_prefix(prefix)
+        .ok_or_else(|| ParseError::at(s, s, format!("{prefix:?}")))
+}
+
+/* -------------------------------------------------------------------------- */
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::{
+        assignment, expect_prefix, ints, key_value, lines_of, lines_with, paragraphs, parse_as,
+        words_of, ParseError,
+    };
+
+    #[test]
+    fn extracts_integers() {
+        assert_eq!(ints::<i64>("Time:      7  15   30").unwrap(), [7, 15, 30]);
+        assert_eq!(ints::<i32>("<x=-1, y=2>").unwrap(), [-1, 2]);
+        assert_eq!(ints::<u32>("3-5 a-7").unwrap(), [3, 5, 7]);
+        assert_eq!(ints::<i32>("--4 - 5").unwrap(), [-4, 5]);
+        assert_eq!(ints::<i32>("no numbers").unwrap(), []);
+
+        let error = ints::<u8>("1 2 300").unwrap_err();
+        assert_eq!((error.line, error.column), (1, 5));
+        assert_eq!(error.text, "300");
+        assert!(ints::<u32>("x=-1").is_err());
+    }
+
+    #[test]
+    fn parses_lines() {
+        assert_eq!(lines_of::<i32>("1\n-2\n3\n").unwrap(), [1, -2, 3]);
+
+        let error = lines_of::<i32>("1\n2\nx\n4").unwrap_err();
+        assert_eq!((error.line, error.column), (3, 1));
+        assert_eq!(error.text, "x");
+        assert_eq!(
+            error.to_string(),
+            "line 3, column 1: expected `i32`, found \"x\""
+        );
+
+        let error = lines_with("1 2\n3 x", words_of::<u8>).unwrap_err();
+        assert_eq!((error.line, error.column, error.text.as_str()), (2, 3, "x"));
+        assert_eq!(words_of::<u8>("  7 8 ").unwrap(), [7, 8]);
+        assert_eq!(parse_as::<u64>("12").unwrap(), 12);
+    }
+
+    #[test]
+    fn splits_paragraphs() {
+        let input = "\na\nb\n\n\nc\n  \nd\n";
+        let blocks: Vec<&str> = paragraphs(input).collect();
+        assert_eq!(blocks, ["a\nb", "c", "d"]);
+        assert_eq!(paragraphs("").count(), 0);
+        assert_eq!(paragraphs("a\r\n\r\nb").collect::<Vec<_>>(), ["a", "b"]);
+    }
+
+    #[test]
+    fn parses_patterns() {
+        assert_eq!(key_value("Game 3 :  1 red").unwrap(), ("Game 3", "1 red"));
+        assert!(key_value("no colon").is_err());
+
+        assert_eq!(assignment("a = (b, c)").unwrap(), ("a", ["b", "c"]));
+        assert_eq!(assignment::<1>("x=5").unwrap(), ("x", ["5"]));
+
+        let error = assignment::<2>("a = (b, c, d)").unwrap_err();
+        assert_eq!((error.column, error.text.as_str()), (12, "d"));
+        assert!(assignment::<2>("a (b, c)").is_err());
+
+        assert_eq!(expect_prefix("Card 1", "Card ").unwrap(), "1");
+        assert_eq!(
+            expect_prefix("Game 1", "Card ").unwrap_err().expected,
+            "\"Card \""
+        );
+    }
+
+    #[test]
+    fn locates_errors_in_the_whole_input() {
+        let input = "1 2\n3 x 5\n";
+        let line = input.lines().nth(1).unwrap();
+        let number = &line[2..3];
+
+        let error = ParseError::at(line, number, "a number").located_in(input, line);
+        assert_eq!((error.line, error.column), (2, 3));
+        assert_eq!(ParseError::at(input, number, "a number").column, 3);
+
+        let error = ParseError::at_end(input, "more lines");
+        assert_eq!((error.line, error.column), (3, 1));
+        assert!(error.to_string().ends_with("found end of input"));
+    }
+}
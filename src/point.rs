@@ -1,43 +1,510 @@
 //! Helper functions for working with points since
 //! 2D operations are a common theme in Advent of Code
 //!
+//! Points use screen coordinates: `x` grows to the right and `y` grows downwards,
+//! so [`UP`] is `(0, -1)` and points are ordered in reading order (by row, then column).
+//!
+//! [`Point`] is generic over its coordinate type and defaults to `i32`. Use e.g. `Point<i64>`
+//! for puzzles with large coordinates, [`Point3`] for 3D puzzles and [`PointN`] for any other dimension.
+//!
 //! ```
-//! use advent_of_code::point::Point;
+//! use advent_of_code::point::{Point, Point3};
 //!
 //! let a = Point::new(1, 2);
 //! let b = Point::new(3, 4);
 //!
 //! assert_eq!(a + b, Point::new(4, 6));
+//! assert_eq!(b - a, Point::new(2, 2));
+//! assert_eq!(a * 3, Point::new(3, 6));
+//! assert_eq!("3,4".parse::<Point>().unwrap(), b);
+//!
+//! let far: Point<i64> = Point::new(5_000_000_000, 0);
+//! assert_eq!(far.manhattan_distance(&Point::new(0, 0)), 5_000_000_000);
 //!
+//! assert_eq!(Point3::new(1, 2, 3).neighbors6().count(), 6);
 //! ```
 
-use std::ops::Add;
+use std::cmp::Ordering;
+use std::error::Error;
+use std::fmt::{Debug, Display};
+use std::hash::Hash;
+use std::ops::{Add, AddAssign, Mul, Neg, Sub, SubAssign};
+use std::str::FromStr;
+
+/// Numeric types that can be used as coordinates of a point.
+pub trait Coordinate:
+    Copy
+    + Ord
+    + Hash
+    + Debug
+    + Display
+    + FromStr
+    + Add<Output = Self>
+    + Sub<Output = Self>
+    + Mul<Output = Self>
+    + AddAssign
+    + SubAssign
+{
+    /// The type of distances between coordinates, i.e. the unsigned counterpart of the type.
+    type Distance: Copy + Ord + Debug + Add<Output = Self::Distance>;
+
+    const ZERO: Self;
+    const ONE: Self;
+
+    fn abs_diff(self, other: Self) -> Self::Distance;
+}
+
+/// Coordinates that can be negative. Required for negation, rotation and neighbours.
+pub trait SignedCoordinate: Coordinate + Neg<Output = Self> {}
+
+macro_rules! impl_coordinate {
+    ($($signed:ty => $unsigned:ty),*) => {
+        $(
+            impl Coordinate for $signed {
+                type Distance = $unsigned;
+                const ZERO: Self = 0;
+                const ONE: Self = 1;
+
+                fn abs_diff(self, other: Self) -> $unsigned {
+                    <$signed>::abs_diff(self, other)
+                }
+            }
+
+            impl SignedCoordinate for $signed {}
+
+            impl Coordinate for $unsigned {
+                type Distance = $unsigned;
+                const ZERO: Self = 0;
+                const ONE: Self = 1;
+
+                fn abs_diff(self, other: Self) -> $unsigned {
+                    <$unsigned>::abs_diff(self, other)
+                }
+            }
+        )*
+    };
+}
+
+impl_coordinate!(i8 => u8, i16 => u16, i32 => u32, i64 => u64, i128 => u128, isize => usize);
+
+/// The offsets `-1`, `0` and `1` in ascending order.
+fn unit_offsets<T: SignedCoordinate>() -> [T; 3] {
+    [-T::ONE, T::ZERO, T::ONE]
+}
+
+/* -------------------------------------------------------------------------- */
 
 #[derive(PartialEq, Eq, Hash, Clone, Copy, Debug)]
-pub struct Point {
-    pub x: i32,
-    pub y: i32,
+pub struct Point<T = i32> {
+    pub x: T,
+    pub y: T,
 }
 
-impl Point {
-    pub const fn new(x: i32, y: i32) -> Self {
+impl<T> Point<T> {
+    pub const fn new(x: T, y: T) -> Self {
         Point { x, y }
     }
+}
 
+impl<T: Coordinate> Point<T> {
     /// The sum of the absolute values of two points
-    pub fn manhattan_distance(&self, other: &Self) -> u32 {
+    pub fn manhattan_distance(&self, other: &Self) -> T::Distance {
         self.x.abs_diff(other.x) + self.y.abs_diff(other.y)
     }
+
+    /// The maximum of the absolute differences of two points,
+    /// i.e. the number of king moves between them
+    pub fn chebyshev_distance(&self, other: &Self) -> T::Distance {
+        self.x.abs_diff(other.x).max(self.y.abs_diff(other.y))
+    }
+
+    /// Converts the point into `(x, y)` indices if it lies within a `width` x `height` area
+    pub fn index_in(self, width: usize, height: usize) -> Option<(usize, usize)>
+    where
+        usize: TryFrom<T>,
+    {
+        let (x, y) = <(usize, usize)>::try_from(self).ok()?;
+        (x < width && y < height).then_some((x, y))
+    }
+}
+
+impl<T: SignedCoordinate> Point<T> {
+    /// Rotates the point by 90° counter-clockwise around the origin, e.g. [`UP`] becomes [`LEFT`]
+    #[must_use]
+    pub fn rotate_left(self) -> Self {
+        Point::new(self.y, -self.x)
+    }
+
+    /// Rotates the point by 90° clockwise around the origin, e.g. [`UP`] becomes [`RIGHT`]
+    #[must_use]
+    pub fn rotate_right(self) -> Self {
+        Point::new(-self.y, self.x)
+    }
+
+    /// The four orthogonally adjacent points, in the order up, right, down, left
+    pub fn neighbors4(self) -> impl Iterator<Item = Point<T>> {
+        let up = Point::new(T::ZERO, -T::ONE);
+        [up, up.rotate_right(), -up, up.rotate_left()]
+            .into_iter()
+            .map(move |direction| self + direction)
+    }
+
+    /// The eight adjacent points including diagonals, in reading order
+    pub fn neighbors8(self) -> impl Iterator<Item = Point<T>> {
+        unit_offsets::<T>()
+            .into_iter()
+            .flat_map(|y| {
+                unit_offsets::<T>()
+                    .into_iter()
+                    .map(move |x| Point::new(x, y))
+            })
+            .filter(|&direction| direction != Point::new(T::ZERO, T::ZERO))
+            .map(move |direction| self + direction)
+    }
+}
+
+/// Points are ordered in reading order: top to bottom, then left to right.
+impl<T: Ord> Ord for Point<T> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.y.cmp(&other.y).then(self.x.cmp(&other.x))
+    }
+}
+
+impl<T: Ord> PartialOrd for Point<T> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+/// A point in 3D space.
+#[derive(PartialEq, Eq, Hash, Clone, Copy, Debug)]
+pub struct Point3<T = i32> {
+    pub x: T,
+    pub y: T,
+    pub z: T,
+}
+
+impl<T> Point3<T> {
+    pub const fn new(x: T, y: T, z: T) -> Self {
+        Point3 { x, y, z }
+    }
+}
+
+impl<T: Coordinate> Point3<T> {
+    /// The sum of the absolute values of two points
+    pub fn manhattan_distance(&self, other: &Self) -> T::Distance {
+        self.x.abs_diff(other.x) + self.y.abs_diff(other.y) + self.z.abs_diff(other.z)
+    }
+
+    /// The maximum of the absolute differences of two points
+    pub fn chebyshev_distance(&self, other: &Self) -> T::Distance {
+        self.x
+            .abs_diff(other.x)
+            .max(self.y.abs_diff(other.y))
+            .max(self.z.abs_diff(other.z))
+    }
+}
+
+impl<T: SignedCoordinate> Point3<T> {
+    /// The six points that share a face with this point
+    pub fn neighbors6(self) -> impl Iterator<Item = Point3<T>> {
+        self.neighbors26()
+            .filter(move |neighbor| neighbor.manhattan_distance(&self) == T::ONE.abs_diff(T::ZERO))
+    }
+
+    /// The 26 points that share a face, edge or corner with this point, ordered by z, y and x
+    pub fn neighbors26(self) -> impl Iterator<Item = Point3<T>> {
+        unit_offsets::<T>()
+            .into_iter()
+            .flat_map(|z| {
+                unit_offsets::<T>().into_iter().flat_map(move |y| {
+                    unit_offsets::<T>()
+                        .into_iter()
+                        .map(move |x| Point3::new(x, y, z))
+                })
+            })
+            .filter(|&direction| direction != Point3::new(T::ZERO, T::ZERO, T::ZERO))
+            .map(move |direction| self + direction)
+    }
+}
+
+/// Points are ordered by layer, then in reading order within a layer.
+impl<T: Ord> Ord for Point3<T> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.z
+            .cmp(&other.z)
+            .then(self.y.cmp(&other.y))
+            .then(self.x.cmp(&other.x))
+    }
+}
+
+impl<T: Ord> PartialOrd for Point3<T> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+/// Implements the arithmetic operators, `Display` and `FromStr` for a point struct with named fields.
+macro_rules! impl_point {
+    ($point:ident, $dimensions:literal, $($field:ident),+) => {
+        impl<T: Coordinate> Add for $point<T> {
+            type Output = $point<T>;
+
+            fn add(self, other: Self) -> Self {
+                $point { $($field: self.$field + other.$field),+ }
+            }
+        }
+
+        impl<T: Coordinate> AddAssign for $point<T> {
+            fn add_assign(&mut self, other: Self) {
+                *self = *self + other;
+            }
+        }
+
+        impl<T: Coordinate> Sub for $point<T> {
+            type Output = $point<T>;
+
+            fn sub(self, other: Self) -> Self {
+                $point { $($field: self.$field - other.$field),+ }
+            }
+        }
+
+        impl<T: Coordinate> SubAssign for $point<T> {
+            fn sub_assign(&mut self, other: Self) {
+                *self = *self - other;
+            }
+        }
+
+        impl<T: SignedCoordinate> Neg for $point<T> {
+            type Output = $point<T>;
+
+            fn neg(self) -> Self {
+                $point { $($field: -self.$field),+ }
+            }
+        }
+
+        impl<T: Coordinate> Mul<T> for $point<T> {
+            type Output = $point<T>;
+
+            fn mul(self, factor: T) -> Self {
+                $point { $($field: self.$field * factor),+ }
+            }
+        }
+
+        impl<T: Display> Display for $point<T> {
+            fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                let coordinates = [$(self.$field.to_string()),+];
+                write!(f, "({})", coordinates.join(", "))
+            }
+        }
+
+        impl<T: FromStr> FromStr for $point<T> {
+            type Err = PointFromStrError;
+
+            fn from_str(s: &str) -> Result<Self, Self::Err> {
+                let [$($field),+] = parse_coordinates::<T, $dimensions>(s)?;
+                Ok($point { $($field),+ })
+            }
+        }
+    };
+}
+
+impl_point!(Point, 2, x, y);
+impl_point!(Point3, 3, x, y, z);
+
+/// Parses `N` comma-separated coordinates, optionally surrounded by parentheses and with whitespace around the values.
+fn parse_coordinates<T: FromStr, const N: usize>(s: &str) -> Result<[T; N], PointFromStrError> {
+    let s = s.trim();
+    let s = s
+        .strip_prefix('(')
+        .and_then(|s| s.strip_suffix(')'))
+        .unwrap_or(s);
+
+    let coordinates = s
+        .split(',')
+        .map(|c| c.trim().parse().map_err(|_| PointFromStrError))
+        .collect::<Result<Vec<T>, _>>()?;
+
+    coordinates.try_into().map_err(|_| PointFromStrError)
+}
+
+/// An error which can be returned when parsing a point.
+#[derive(Debug)]
+pub struct PointFromStrError;
+
+impl Error for PointFromStrError {}
+
+impl Display for PointFromStrError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("expecting comma-separated coordinates, e.g. `x,y`")
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+/// Converts `(x, y)` indices into a point, fails if an index does not fit into the coordinate type.
+impl<T: TryFrom<usize>> TryFrom<(usize, usize)> for Point<T> {
+    type Error = T::Error;
+
+    fn try_from((x, y): (usize, usize)) -> Result<Self, Self::Error> {
+        Ok(Point::new(x.try_into()?, y.try_into()?))
+    }
+}
+
+/// Converts a point into `(x, y)` indices, fails if a coordinate is negative.
+impl<T> TryFrom<Point<T>> for (usize, usize)
+where
+    usize: TryFrom<T>,
+{
+    type Error = <usize as TryFrom<T>>::Error;
+
+    fn try_from(point: Point<T>) -> Result<Self, Self::Error> {
+        Ok((point.x.try_into()?, point.y.try_into()?))
+    }
 }
 
-impl Add for Point {
-    type Output = Point;
+/* -------------------------------------------------------------------------- */
 
-    fn add(self, other: Self) -> Self {
-        Point::new(self.x + other.x, self.y + other.y)
+/// A point with an arbitrary number of dimensions.
+#[derive(PartialEq, Eq, Hash, Clone, Copy, Debug)]
+pub struct PointN<const N: usize, T = i32>(pub [T; N]);
+
+impl<const N: usize, T> PointN<N, T> {
+    pub const fn new(coordinates: [T; N]) -> Self {
+        PointN(coordinates)
     }
 }
 
+impl<const N: usize, T: Coordinate> PointN<N, T> {
+    /// The sum of the absolute values of two points
+    pub fn manhattan_distance(&self, other: &Self) -> T::Distance {
+        let mut distances = self.0.iter().zip(other.0).map(|(&a, b)| a.abs_diff(b));
+        let first = distances
+            .next()
+            .unwrap_or_else(|| T::ZERO.abs_diff(T::ZERO));
+        distances.fold(first, |sum, d| sum + d)
+    }
+
+    /// The maximum of the absolute differences of two points
+    pub fn chebyshev_distance(&self, other: &Self) -> T::Distance {
+        self.0
+            .iter()
+            .zip(other.0)
+            .map(|(&a, b)| a.abs_diff(b))
+            .max()
+            .unwrap_or_else(|| T::ZERO.abs_diff(T::ZERO))
+    }
+}
+
+impl<const N: usize, T: SignedCoordinate> PointN<N, T> {
+    /// The `2 * N` points that differ by one in exactly one coordinate
+    pub fn neighbors_orthogonal(self) -> impl Iterator<Item = PointN<N, T>> {
+        (0..N).flat_map(move |axis| {
+            [-T::ONE, T::ONE].into_iter().map(move |offset| {
+                let mut neighbor = self;
+                neighbor.0[axis] += offset;
+                neighbor
+            })
+        })
+    }
+
+    /// The `3^N - 1` points that differ by at most one in every coordinate
+    pub fn neighbors(self) -> impl Iterator<Item = PointN<N, T>> {
+        let count = 3_usize.pow(u32::try_from(N).expect("dimension should fit into u32"));
+        (0..count)
+            .filter(move |&idx| idx != count / 2)
+            .map(move |mut idx| {
+                let mut neighbor = self;
+                // interpret idx as a base 3 number, one digit per axis.
+                for axis in 0..N {
+                    neighbor.0[axis] += unit_offsets::<T>()[idx % 3];
+                    idx /= 3;
+                }
+                neighbor
+            })
+    }
+}
+
+impl<const N: usize, T: Coordinate> Add for PointN<N, T> {
+    type Output = PointN<N, T>;
+
+    fn add(mut self, other: Self) -> Self {
+        self += other;
+        self
+    }
+}
+
+impl<const N: usize, T: Coordinate> AddAssign for PointN<N, T> {
+    fn add_assign(&mut self, other: Self) {
+        self.0.iter_mut().zip(other.0).for_each(|(a, b)| *a += b);
+    }
+}
+
+impl<const N: usize, T: Coordinate> Sub for PointN<N, T> {
+    type Output = PointN<N, T>;
+
+    fn sub(mut self, other: Self) -> Self {
+        self -= other;
+        self
+    }
+}
+
+impl<const N: usize, T: Coordinate> SubAssign for PointN<N, T> {
+    fn sub_assign(&mut self, other: Self) {
+        self.0.iter_mut().zip(other.0).for_each(|(a, b)| *a -= b);
+    }
+}
+
+impl<const N: usize, T: SignedCoordinate> Neg for PointN<N, T> {
+    type Output = PointN<N, T>;
+
+    fn neg(self) -> Self {
+        PointN(self.0.map(|c| -c))
+    }
+}
+
+impl<const N: usize, T: Coordinate> Mul<T> for PointN<N, T> {
+    type Output = PointN<N, T>;
+
+    fn mul(self, factor: T) -> Self {
+        PointN(self.0.map(|c| c * factor))
+    }
+}
+
+/// Points are ordered by their last coordinate first, which matches the reading order of [`Point`] for `N = 2`.
+impl<const N: usize, T: Ord> Ord for PointN<N, T> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.0.iter().rev().cmp(other.0.iter().rev())
+    }
+}
+
+impl<const N: usize, T: Ord> PartialOrd for PointN<N, T> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<const N: usize, T: Display> Display for PointN<N, T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let coordinates: Vec<String> = self.0.iter().map(ToString::to_string).collect();
+        write!(f, "({})", coordinates.join(", "))
+    }
+}
+
+impl<const N: usize, T: FromStr> FromStr for PointN<N, T> {
+    type Err = PointFromStrError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        parse_coordinates(s).map(PointN)
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
 // Common points and directions. Useful for looking
 // around a point or moving in a direction
 pub const UP: Point = Point::new(0, -1);
@@ -49,6 +516,8 @@ pub const TOP_RIGHT: Point = Point::new(1, -1);
 pub const BOTTOM_LEFT: Point = Point::new(-1, 1);
 pub const BOTTOM_RIGHT: Point = Point::new(1, 1);
 
+pub const ORTHOGONALS: [Point; 4] = [UP, RIGHT, DOWN, LEFT];
+
 pub const DIAGONALS: [Point; 8] = [
     TOP_LEFT,
     UP,
@@ -59,3 +528,139 @@ pub const DIAGONALS: [Point; 8] = [
     DOWN,
     BOTTOM_RIGHT,
 ];
+
+/* -------------------------------------------------------------------------- */
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::{Point, Point3, PointN, DIAGONALS, DOWN, LEFT, ORTHOGONALS, RIGHT, UP};
+
+    #[test]
+    fn arithmetic() {
+        let mut a = Point::new(1, 2);
+        assert_eq!(a - Point::new(3, 5), Point::new(-2, -3));
+        assert_eq!(-a, Point::new(-1, -2));
+        assert_eq!(a * -2, Point::new(-2, -4));
+        a += Point::new(1, 1);
+        assert_eq!(a, Point::new(2, 3));
+        a -= Point::new(2, 3);
+        assert_eq!(a, Point::new(0, 0));
+    }
+
+    #[test]
+    fn rotation() {
+        assert_eq!(UP.rotate_right(), RIGHT);
+        assert_eq!(RIGHT.rotate_right(), DOWN);
+        assert_eq!(UP.rotate_left(), LEFT);
+        assert_eq!(
+            Point::new(3, -7).rotate_left().rotate_right(),
+            Point::new(3, -7)
+        );
+    }
+
+    #[test]
+    fn distances() {
+        let a = Point::new(1, 2);
+        let b = Point::new(-3, 4);
+        assert_eq!(a.manhattan_distance(&b), 6_u32);
+        assert_eq!(a.chebyshev_distance(&b), 4_u32);
+    }
+
+    #[test]
+    fn neighbors() {
+        let p = Point::new(5, 5);
+        let n4: Vec<Point> = p.neighbors4().collect();
+        assert_eq!(n4, ORTHOGONALS.map(|d| p + d).to_vec());
+        let n8: Vec<Point> = p.neighbors8().collect();
+        assert_eq!(n8, DIAGONALS.map(|d| p + d).to_vec());
+        assert!(n8.windows(2).all(|w| w[0] < w[1]));
+    }
+
+    #[test]
+    fn reading_order() {
+        let mut points = vec![Point::new(2, 1), Point::new(0, 2), Point::new(1, 1)];
+        points.sort();
+        assert_eq!(
+            points,
+            vec![Point::new(1, 1), Point::new(2, 1), Point::new(0, 2)]
+        );
+    }
+
+    #[test]
+    fn parse_and_display() {
+        assert_eq!("3,-4".parse::<Point>().unwrap(), Point::new(3, -4));
+        assert_eq!(" ( 3 , -4 ) ".parse::<Point>().unwrap(), Point::new(3, -4));
+        assert!("3;4".parse::<Point>().is_err());
+        assert!("3,x".parse::<Point>().is_err());
+        assert!("3,4,5".parse::<Point>().is_err());
+        let p = Point::new(-1, 7);
+        assert_eq!(p.to_string(), "(-1, 7)");
+        assert_eq!(p.to_string().parse::<Point>().unwrap(), p);
+    }
+
+    #[test]
+    fn index_conversion() {
+        assert_eq!(Point::try_from((3, 4)).unwrap(), Point::new(3, 4));
+        assert!(Point::<i32>::try_from((usize::MAX, 0)).is_err());
+        assert_eq!(
+            <(usize, usize)>::try_from(Point::new(3, 4)).unwrap(),
+            (3, 4)
+        );
+        assert!(<(usize, usize)>::try_from(Point::new(-1, 4)).is_err());
+        assert_eq!(Point::new(2, 1).index_in(3, 2), Some((2, 1)));
+        assert_eq!(Point::new(3, 1).index_in(3, 2), None);
+        assert_eq!(Point::new(0, -1).index_in(3, 2), None);
+    }
+
+    #[test]
+    fn generic_coordinates() {
+        let a: Point<i64> = Point::new(3_000_000_000, -3_000_000_000);
+        assert_eq!(a.manhattan_distance(&Point::new(0, 0)), 6_000_000_000_u64);
+        assert_eq!(a * 2, Point::new(6_000_000_000, -6_000_000_000));
+        assert_eq!(a.neighbors4().count(), 4);
+
+        let b: Point<usize> = Point::new(3, 4);
+        assert_eq!(b.manhattan_distance(&Point::new(5, 1)), 5);
+        assert_eq!(b.index_in(4, 5), Some((3, 4)));
+        assert_eq!("7,8".parse::<Point<u8>>().unwrap(), Point::new(7, 8));
+    }
+
+    #[test]
+    fn point3() {
+        let a = Point3::new(1, 2, 3);
+        let b = Point3::new(-1, 0, 7);
+        assert_eq!(a + b, Point3::new(0, 2, 10));
+        assert_eq!(a - b, Point3::new(2, 2, -4));
+        assert_eq!(-a * 2, Point3::new(-2, -4, -6));
+        assert_eq!(a.manhattan_distance(&b), 8);
+        assert_eq!(a.chebyshev_distance(&b), 4);
+        assert_eq!(a.neighbors6().count(), 6);
+        assert!(a.neighbors6().all(|n| n.manhattan_distance(&a) == 1));
+        let n26: Vec<Point3> = a.neighbors26().collect();
+        assert_eq!(n26.len(), 26);
+        assert!(n26.windows(2).all(|w| w[0] < w[1]));
+        assert_eq!("1, 2, 3".parse::<Point3>().unwrap(), a);
+        assert_eq!(a.to_string(), "(1, 2, 3)");
+    }
+
+    #[test]
+    fn point_n() {
+        let a = PointN::new([1, 2, 3, 4]);
+        let b = PointN::new([0, 0, 0, 0]);
+        assert_eq!(a - a, b);
+        assert_eq!(a + a, a * 2);
+        assert_eq!(-a, PointN::new([-1, -2, -3, -4]));
+        assert_eq!(a.manhattan_distance(&b), 10);
+        assert_eq!(a.chebyshev_distance(&b), 4);
+        assert_eq!(b.neighbors_orthogonal().count(), 8);
+        assert!(b
+            .neighbors_orthogonal()
+            .all(|n| n.manhattan_distance(&b) == 1));
+        let neighbors: Vec<PointN<4>> = b.neighbors().collect();
+        assert_eq!(neighbors.len(), 80);
+        assert!(neighbors.iter().all(|n| n.chebyshev_distance(&b) == 1));
+        assert!(PointN::new([5, 1]) < PointN::new([0, 2]));
+        assert_eq!("(1,2,3,4)".parse::<PointN<4>>().unwrap(), a);
+        assert!("1,2,3".parse::<PointN<4>>().is_err());
+    }
+}
@@ -0,0 +1,501 @@
+//! Sets of half-open integer ranges and piecewise-linear maps between them
+//!
+//! Puzzles that work on huge ranges of numbers can rarely afford to look at every number.
+//! [`RangeSet`] keeps a set of numbers as sorted, disjoint ranges and implements set algebra on them,
+//! [`RangeMap`] shifts parts of the number line (like the almanac tables of 2023 day 5) and maps whole sets at once.
+//!
+//! ```
+//! use advent_of_code::ranges::{RangeMap, RangeSet};
+//!
+//! let set: RangeSet = [0..10, 5..15, 20..25].into_iter().collect();
+//! assert_eq!(set.ranges(), [0..15, 20..25]);
+//! assert_eq!(set.len(), 20);
+//!
+//! let mut map = RangeMap::new();
+//! assert!(map.insert(10..20, 100));
+//! assert_eq!(map.get(15), 105);
+//! assert_eq!(map.get(25), 25);
+//! assert_eq!(map.map_set(&set).ranges(), [0..10, 20..25, 100..105]);
+//!
+//! let mut next = RangeMap::new();
+//! assert!(next.insert(100..110, 0));
+//! assert_eq!(map.then(&next).get(15), 5);
+//! ```
+
+use std::fmt::Debug;
+use std::ops::{Add, Range, Sub};
+
+/// Numbers that can be the bounds of a range.
+pub trait Value: Copy + Ord + Debug + Add<Output = Self> + Sub<Output = Self> + Default {}
+
+impl<T: Copy + Ord + Debug + Add<Output = T> + Sub<Output = T> + Default> Value for T {}
+
+/// A set of numbers, stored as sorted, disjoint and non-adjacent half-open ranges.
+#[derive(PartialEq, Eq, Hash, Clone, Debug, Default)]
+pub struct RangeSet<T = i64> {
+    ranges: Vec<Range<T>>,
+}
+
+impl<T: Value> RangeSet<T> {
+    pub fn new() -> Self {
+        RangeSet { ranges: vec![] }
+    }
+
+    /// The ranges of the set in ascending order
+    pub fn ranges(&self) -> &[Range<T>] {
+        &self.ranges
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.ranges.is_empty()
+    }
+
+    /// The number of values in the set
+    pub fn len(&self) -> T {
+        self.ranges
+            .iter()
+            .fold(T::default(), |len, range| len + (range.end - range.start))
+    }
+
+    pub fn contains(&self, value: T) -> bool {
+        let idx = self.ranges.partition_point(|range| range.end <= value);
+        self.ranges
+            .get(idx)
+            .is_some_and(|range| range.start <= value)
+    }
+
+    /// The smallest value of the set
+    pub fn min(&self) -> Option<T> {
+        self.ranges.first().map(|range| range.start)
+    }
+
+    /// One past the largest value of the set
+    pub fn end(&self) -> Option<T> {
+        self.ranges.last().map(|range| range.end)
+    }
+
+    /// Adds all values of `range` to the set. Empty ranges are ignored.
+    pub fn insert(&mut self, range: Range<T>) {
+        if range.is_empty() {
+            return;
+        }
+
+        // all ranges that overlap or touch `range` are merged into it.
+        let first = self.ranges.partition_point(|r| r.end < range.start);
+        let last = self.ranges.partition_point(|r| r.start <= range.end);
+        let merged = if first < last {
+            self.ranges[first].start.min(range.start)..self.ranges[last - 1].end.max(range.end)
+        } else {
+            range
+        };
+        self.ranges.splice(first..last, [merged]);
+    }
+
+    /// Removes all values of `range` from the set.
+    pub fn remove(&mut self, range: Range<T>) {
+        if range.is_empty() {
+            return;
+        }
+
+        let first = self.ranges.partition_point(|r| r.end <= range.start);
+        let last = self.ranges.partition_point(|r| r.start < range.end);
+        if first == last {
+            return;
+        }
+
+        // only the first and last overlapping ranges can stick out of `range`.
+        let (head, tail) = (self.ranges[first].start, self.ranges[last - 1].end);
+        let mut remaining = vec![];
+        if head < range.start {
+            remaining.push(head..range.start);
+        }
+        if tail > range.end {
+            remaining.push(range.end..tail);
+        }
+        self.ranges.splice(first..last, remaining);
+    }
+
+    /// All values that are in either set
+    #[must_use]
+    pub fn union(&self, other: &Self) -> Self {
+        let mut result = self.clone();
+        for range in &other.ranges {
+            result.insert(range.clone());
+        }
+        result
+    }
+
+    /// All values that are in both sets
+    #[must_use]
+    pub fn intersection(&self, other: &Self) -> Self {
+        let mut ranges = vec![];
+        let (mut i, mut j) = (0, 0);
+
+        while let (Some(a), Some(b)) = (self.ranges.get(i), other.ranges.get(j)) {
+            let start = a.start.max(b.start);
+            let end = a.end.min(b.end);
+            if start < end {
+                ranges.push(start..end);
+            }
+            // the range that ends first cannot overlap anything else.
+            if a.end < b.end {
+                i += 1;
+            } else {
+                j += 1;
+            }
+        }
+
+        RangeSet { ranges }
+    }
+
+    /// All values of this set that are not in `other`
+    #[must_use]
+    pub fn difference(&self, other: &Self) -> Self {
+        let mut result = self.clone();
+        for range in &other.ranges {
+            result.remove(range.clone());
+        }
+        result
+    }
+
+    /// Splits the set into the values inside and outside of `range`.
+    pub fn split(&self, range: Range<T>) -> (Self, Self) {
+        let range = RangeSet::from(range);
+        (self.intersection(&range), self.difference(&range))
+    }
+}
+
+impl<T: Value> From<Range<T>> for RangeSet<T> {
+    fn from(range: Range<T>) -> Self {
+        let mut set = RangeSet::new();
+        set.insert(range);
+        set
+    }
+}
+
+impl<T: Value> FromIterator<Range<T>> for RangeSet<T> {
+    fn from_iter<I: IntoIterator<Item = Range<T>>>(iter: I) -> Self {
+        let mut set = RangeSet::new();
+        set.extend(iter);
+        set
+    }
+}
+
+impl<T: Value> Extend<Range<T>> for RangeSet<T> {
+    fn extend<I: IntoIterator<Item = Range<T>>>(&mut self, iter: I) {
+        for range in iter {
+            self.insert(range);
+        }
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+/// A piecewise-linear map that moves source ranges to new start values and keeps every other value as it is.
+#[derive(PartialEq, Eq, Hash, Clone, Debug, Default)]
+pub struct RangeMap<T = i64> {
+    /// Source ranges and the destination of their start, sorted and disjoint
+    entries: Vec<(Range<T>, T)>,
+}
+
+impl<T: Value> RangeMap<T> {
+    pub fn new() -> Self {
+        RangeMap { entries: vec![] }
+    }
+
+    /// The source ranges and the destination of their start, in ascending order
+    pub fn entries(&self) -> &[(Range<T>, T)] {
+        &self.entries
+    }
+
+    /// Maps the values of `source` to `destination..destination + source.len()`.
+    /// Returns `false` and leaves the map unchanged if `source` overlaps a source range that is already mapped.
+    #[must_use]
+    pub fn insert(&mut self, source: Range<T>, destination: T) -> bool {
+        if source.is_empty() {
+            return true;
+        }
+
+        let idx = self
+            .entries
+            .partition_point(|(range, _)| range.end <= source.start);
+        if self
+            .entries
+            .get(idx)
+            .is_some_and(|(range, _)| range.start < source.end)
+        {
+            return false;
+        }
+
+        self.entries.insert(idx, (source, destination));
+        true
+    }
+
+    /// The value that `value` is mapped to
+    pub fn get(&self, value: T) -> T {
+        let idx = self
+            .entries
+            .partition_point(|(range, _)| range.end <= value);
+        match self.entries.get(idx) {
+            Some((range, destination)) if range.start <= value => {
+                *destination + (value - range.start)
+            }
+            _ => value,
+        }
+    }
+
+    /// All values that are mapped to `value`, in ascending order.
+    /// Unlike [`RangeMap::get`], this needs to look at every entry.
+    pub fn preimage(&self, value: T) -> Vec<T> {
+        let mut values: Vec<T> = self.preimages(value).collect();
+        values.sort();
+        values
+    }
+
+    /// The values of [`RangeMap::preimage`] in no particular order, without collecting them.
+    pub fn preimages(&self, value: T) -> impl Iterator<Item = T> + '_ {
+        let shifted = self
+            .entries
+            .iter()
+            .filter(move |(range, destination)| {
+                *destination <= value && value < *destination + (range.end - range.start)
+            })
+            .map(move |(range, destination)| range.start + (value - *destination));
+
+        let is_mapped = self.entries.iter().any(|(range, _)| range.contains(&value));
+        shifted.chain((!is_mapped).then_some(value))
+    }
+
+    /// Cuts `set` at the boundaries of the source ranges.
+    /// Returns every piece together with the range it is mapped to, in ascending order of the pieces.
+    pub fn split(&self, set: &RangeSet<T>) -> Vec<(Range<T>, Range<T>)> {
+        let mut pieces = vec![];
+
+        for range in set.ranges() {
+            let mut start = range.start;
+            let first = self
+                .entries
+                .partition_point(|(source, _)| source.end <= start);
+
+            for (source, destination) in &self.entries[first..] {
+                if start >= range.end || source.start >= range.end {
+                    break;
+                }
+                if start < source.start {
+                    pieces.push((start..source.start, start..source.start));
+                    start = source.start;
+                }
+                let end = range.end.min(source.end);
+                let offset = start - source.start;
+                pieces.push((
+                    start..end,
+                    *destination + offset..*destination + offset + (end - start),
+                ));
+                start = end;
+            }
+
+            if start < range.end {
+                pieces.push((start..range.end, start..range.end));
+            }
+        }
+
+        pieces
+    }
+
+    /// The map that sends every value `v` to `next.get(self.get(v))`.
+    pub fn then(&self, next: &Self) -> Self {
+        let mut composed = RangeMap::new();
+
+        // values that this map moves end up wherever `next` sends their image.
+        for (source, destination) in &self.entries {
+            let image = RangeSet::from(*destination..*destination + (source.end - source.start));
+            for (piece, target) in next.split(&image) {
+                let start = source.start + (piece.start - *destination);
+                let _ = composed.insert(start..start + (piece.end - piece.start), target.start);
+            }
+        }
+
+        // all other values are only moved by `next`.
+        let moved: RangeSet<T> = self
+            .entries
+            .iter()
+            .map(|(source, _)| source.clone())
+            .collect();
+        for (source, destination) in &next.entries {
+            for piece in RangeSet::from(source.clone()).difference(&moved).ranges() {
+                let _ = composed.insert(piece.clone(), *destination + (piece.start - source.start));
+            }
+        }
+
+        composed
+    }
+
+    /// The image of `set`, i.e. the set of all values that the values of `set` are mapped to
+    pub fn map_set(&self, set: &RangeSet<T>) -> RangeSet<T> {
+        self.split(set)
+            .into_iter()
+            .map(|(_, destination)| destination)
+            .collect()
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use std::collections::BTreeSet;
+
+    use super::{RangeMap, RangeSet};
+    use crate::testing::Rng;
+
+    fn random_set(rng: &mut Rng) -> RangeSet {
+        (0..rng.below(5))
+            .map(|_| {
+                let start = rng.below(40) as i64;
+                start..start + rng.below(10) as i64
+            })
+            .collect()
+    }
+
+    fn random_map(rng: &mut Rng) -> RangeMap {
+        let mut map = RangeMap::new();
+        for _ in 0..rng.below(4) {
+            let start = rng.below(40) as i64;
+            let len = rng.below(10) as i64;
+            let _ = map.insert(start..start + len, rng.below(60) as i64);
+        }
+        map
+    }
+
+    fn values(set: &RangeSet) -> BTreeSet<i64> {
+        set.ranges().iter().cloned().flatten().collect()
+    }
+
+    #[test]
+    fn normalizes_ranges() {
+        let set: RangeSet = [5..8, 0..2, 2..3, 7..10, 12..12].into_iter().collect();
+        assert_eq!(set.ranges(), [0..3, 5..10]);
+        assert_eq!(set.len(), 8);
+        assert_eq!((set.min(), set.end()), (Some(0), Some(10)));
+        assert!(set.contains(2));
+        assert!(!set.contains(3));
+        assert!(set.contains(9));
+        assert!(!set.contains(10));
+
+        let mut set = set;
+        set.insert(-5..20);
+        assert_eq!(set.ranges(), [-5..20]);
+        set.remove(0..5);
+        set.remove(10..11);
+        assert_eq!(set.ranges(), [-5..0, 5..10, 11..20]);
+        set.remove(-10..30);
+        assert!(set.is_empty());
+    }
+
+    #[test]
+    fn set_algebra() {
+        let a: RangeSet = [0..10, 20..30].into_iter().collect();
+        let b: RangeSet = [5..25].into_iter().collect();
+        assert_eq!(a.union(&b).ranges(), [0..30]);
+        assert_eq!(a.intersection(&b).ranges(), [5..10, 20..25]);
+        assert_eq!(a.difference(&b).ranges(), [0..5, 25..30]);
+        assert_eq!(b.difference(&a).ranges(), [10..20]);
+
+        let (inside, outside) = a.split(8..22);
+        assert_eq!(inside.ranges(), [8..10, 20..22]);
+        assert_eq!(outside.ranges(), [0..8, 22..30]);
+    }
+
+    #[test]
+    fn set_algebra_matches_btreeset() {
+        let mut rng = Rng::new(0x1357_9bdf_2468_ace0);
+        for _ in 0..500 {
+            let a = random_set(&mut rng);
+            let b = random_set(&mut rng);
+            let (va, vb) = (values(&a), values(&b));
+
+            assert_eq!(values(&a.union(&b)), &va | &vb);
+            assert_eq!(values(&a.intersection(&b)), &va & &vb);
+            assert_eq!(values(&a.difference(&b)), &va - &vb);
+            assert_eq!(a.len(), va.len() as i64);
+            assert!(a.ranges().windows(2).all(|w| w[0].end < w[1].start));
+        }
+    }
+
+    #[test]
+    fn maps_values() {
+        let mut map = RangeMap::new();
+        assert!(map.insert(98..100, 50));
+        assert!(map.insert(50..98, 52));
+        assert!(!map.insert(90..110, 0));
+        assert_eq!(map.entries().len(), 2);
+
+        assert_eq!(map.get(79), 81);
+        assert_eq!(map.get(14), 14);
+        assert_eq!(map.get(99), 51);
+        assert_eq!(map.get(100), 100);
+
+        assert_eq!(map.preimage(51), [99]);
+        assert_eq!(map.preimage(81), [79]);
+        assert_eq!(map.preimage(14), [14]);
+        assert_eq!(map.preimage(98), [96]);
+
+        let mut map = RangeMap::new();
+        assert!(map.insert(0..10, 5));
+        assert_eq!(map.preimage(2), []);
+        assert_eq!(map.preimage(12), [7, 12]);
+    }
+
+    #[test]
+    fn splits_and_maps_sets() {
+        let mut map = RangeMap::new();
+        assert!(map.insert(10..20, 100));
+        assert!(map.insert(20..25, 0));
+
+        let set: RangeSet = [5..12, 18..30].into_iter().collect();
+        assert_eq!(
+            map.split(&set),
+            [
+                (5..10, 5..10),
+                (10..12, 100..102),
+                (18..20, 108..110),
+                (20..25, 0..5),
+                (25..30, 25..30),
+            ]
+        );
+        assert_eq!(
+            map.map_set(&set).ranges(),
+            [0..10, 25..30, 100..102, 108..110]
+        );
+    }
+
+    #[test]
+    fn map_set_matches_mapping_every_value() {
+        let mut rng = Rng::new(0x0bad_cafe_dead_beef);
+        for _ in 0..500 {
+            let map = random_map(&mut rng);
+            let set = random_set(&mut rng);
+
+            let expected: BTreeSet<i64> = values(&set).into_iter().map(|v| map.get(v)).collect();
+            assert_eq!(values(&map.map_set(&set)), expected);
+        }
+    }
+
+    #[test]
+    fn composes_maps() {
+        let mut rng = Rng::new(0x2468_1357_fedc_ba98);
+        for _ in 0..500 {
+            let first = random_map(&mut rng);
+            let second = random_map(&mut rng);
+            let composed = first.then(&second);
+
+            assert!(composed
+                .entries()
+                .windows(2)
+                .all(|w| w[0].0.end <= w[1].0.start));
+            for value in -5..80 {
+                assert_eq!(composed.get(value), second.get(first.get(value)));
+            }
+        }
+    }
+}
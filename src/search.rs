@@ -0,0 +1,406 @@
+//! Generic graph searches over a successor function
+//!
+//! Nodes can be anything that is `Clone + Eq + Hash`: points, strings or whole puzzle states.
+//! Graphs are never built up front. Instead, every search calls a `successors` closure to find
+//! the neighbours of a node, and an `is_goal` closure decides when the search can stop.
+//! Pass `|_| false` to explore everything that is reachable.
+//!
+//! ```
+//! use advent_of_code::grid::Grid;
+//! use advent_of_code::search;
+//!
+//! let grid: Grid<char> = "S.#\n#..\n..E".parse().unwrap();
+//! let start = grid.find(&'S').unwrap();
+//!
+//! let search = search::bfs(
+//!     start,
+//!     |&p| grid.neighbors4(p).filter(|&n| grid[n] != '#').collect::<Vec<_>>(),
+//!     |&p| grid[p] == 'E',
+//! );
+//! assert_eq!(search.target_distance(), Some(4));
+//! assert_eq!(search.target_path().unwrap().len(), 5);
+//! ```
+
+use std::cmp::Reverse;
+use std::collections::hash_map::Entry;
+use std::collections::{BinaryHeap, HashMap, HashSet, VecDeque};
+use std::hash::Hash;
+use std::ops::Add;
+
+/// The result of a search: the distance to every visited node, how it was reached and the goal if one was found.
+#[derive(Clone, Debug)]
+pub struct Search<N, C = usize> {
+    distances: HashMap<N, C>,
+    predecessors: HashMap<N, N>,
+    target: Option<N>,
+}
+
+impl<N: Clone + Eq + Hash, C: Copy> Search<N, C> {
+    fn new(start: N, zero: C) -> Self {
+        Search {
+            distances: HashMap::from([(start, zero)]),
+            predecessors: HashMap::new(),
+            target: None,
+        }
+    }
+
+    /// The first node that satisfied `is_goal`
+    pub fn target(&self) -> Option<&N> {
+        self.target.as_ref()
+    }
+
+    pub fn target_distance(&self) -> Option<C> {
+        self.distance(self.target.as_ref()?)
+    }
+
+    /// The path from the start to the target, both included
+    pub fn target_path(&self) -> Option<Vec<N>> {
+        self.path_to(self.target.as_ref()?)
+    }
+
+    /// Checks if `node` was visited by the search
+    pub fn contains(&self, node: &N) -> bool {
+        self.distances.contains_key(node)
+    }
+
+    /// The distance from the start to `node`, `None` if it was not visited
+    pub fn distance(&self, node: &N) -> Option<C> {
+        self.distances.get(node).copied()
+    }
+
+    /// The node that `node` was reached from, `None` for the start and unvisited nodes
+    pub fn predecessor(&self, node: &N) -> Option<&N> {
+        self.predecessors.get(node)
+    }
+
+    /// The path from the start to `node`, both included
+    pub fn path_to(&self, node: &N) -> Option<Vec<N>> {
+        if !self.contains(node) {
+            return None;
+        }
+
+        let mut path = vec![node.clone()];
+        while let Some(predecessor) = self.predecessor(path.last()?) {
+            path.push(predecessor.clone());
+        }
+        path.reverse();
+        Some(path)
+    }
+
+    pub fn distances(&self) -> &HashMap<N, C> {
+        &self.distances
+    }
+
+    pub fn predecessors(&self) -> &HashMap<N, N> {
+        &self.predecessors
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+/// Breadth-first search, finds the shortest paths in unweighted graphs.
+pub fn bfs<N, I>(
+    start: N,
+    mut successors: impl FnMut(&N) -> I,
+    mut is_goal: impl FnMut(&N) -> bool,
+) -> Search<N>
+where
+    N: Clone + Eq + Hash,
+    I: IntoIterator<Item = N>,
+{
+    let mut search = Search::new(start.clone(), 0);
+    let mut queue = VecDeque::from([start]);
+
+    while let Some(node) = queue.pop_front() {
+        if is_goal(&node) {
+            search.target = Some(node);
+            break;
+        }
+
+        let distance = search.distances[&node] + 1;
+        for next in successors(&node) {
+            if let Entry::Vacant(entry) = search.distances.entry(next.clone()) {
+                entry.insert(distance);
+                search.predecessors.insert(next.clone(), node.clone());
+                queue.push_back(next);
+            }
+        }
+    }
+
+    search
+}
+
+/// Depth-first search. Distances are the depth of a node in the search tree, not the length of the shortest path.
+pub fn dfs<N, I>(
+    start: N,
+    mut successors: impl FnMut(&N) -> I,
+    mut is_goal: impl FnMut(&N) -> bool,
+) -> Search<N>
+where
+    N: Clone + Eq + Hash,
+    I: IntoIterator<Item = N>,
+{
+    let mut search = Search::new(start.clone(), 0);
+    let mut visited = HashSet::new();
+    let mut stack = vec![start];
+
+    while let Some(node) = stack.pop() {
+        if !visited.insert(node.clone()) {
+            continue;
+        }
+        if is_goal(&node) {
+            search.target = Some(node);
+            break;
+        }
+
+        let depth = search.distances[&node] + 1;
+        let mut next_nodes: Vec<N> = successors(&node)
+            .into_iter()
+            .filter(|next| !visited.contains(next))
+            .collect();
+        // visit the successors in the order they were returned.
+        next_nodes.reverse();
+
+        for next in next_nodes {
+            search.distances.insert(next.clone(), depth);
+            search.predecessors.insert(next.clone(), node.clone());
+            stack.push(next);
+        }
+    }
+
+    // nodes that were pushed but never visited are not part of the search tree.
+    search.distances.retain(|node, _| visited.contains(node));
+    search.predecessors.retain(|node, _| visited.contains(node));
+    search
+}
+
+/// Dijkstra's algorithm, finds the cheapest paths in graphs with non-negative costs.
+/// `successors` returns the neighbours of a node together with the cost of moving there.
+pub fn dijkstra<N, C, I>(
+    start: N,
+    successors: impl FnMut(&N) -> I,
+    is_goal: impl FnMut(&N) -> bool,
+) -> Search<N, C>
+where
+    N: Clone + Eq + Hash,
+    C: Copy + Ord + Default + Add<Output = C>,
+    I: IntoIterator<Item = (N, C)>,
+{
+    astar(start, successors, |_| C::default(), is_goal)
+}
+
+/// A* search, like [`dijkstra`] but explores nodes in the order of `cost + heuristic(node)`.
+/// The heuristic must never overestimate the remaining cost to a goal, otherwise the result might not be the cheapest path.
+pub fn astar<N, C, I>(
+    start: N,
+    mut successors: impl FnMut(&N) -> I,
+    mut heuristic: impl FnMut(&N) -> C,
+    mut is_goal: impl FnMut(&N) -> bool,
+) -> Search<N, C>
+where
+    N: Clone + Eq + Hash,
+    C: Copy + Ord + Default + Add<Output = C>,
+    I: IntoIterator<Item = (N, C)>,
+{
+    let mut search = Search::new(start.clone(), C::default());
+    // nodes are kept outside of the heap so that they don't need to implement `Ord`.
+    let mut nodes = vec![(start.clone(), C::default())];
+    let mut heap = BinaryHeap::from([Reverse((heuristic(&start), 0))]);
+    let mut closed = HashSet::new();
+
+    while let Some(Reverse((_, idx))) = heap.pop() {
+        let (node, cost) = nodes[idx].clone();
+        if search.distances[&node] < cost || !closed.insert(node.clone()) {
+            continue;
+        }
+        if is_goal(&node) {
+            search.target = Some(node);
+            break;
+        }
+
+        for (next, step_cost) in successors(&node) {
+            let next_cost = cost + step_cost;
+            let is_cheaper = search
+                .distances
+                .get(&next)
+                .is_none_or(|&known| next_cost < known);
+
+            if is_cheaper && !closed.contains(&next) {
+                search.distances.insert(next.clone(), next_cost);
+                search.predecessors.insert(next.clone(), node.clone());
+                heap.push(Reverse((next_cost + heuristic(&next), nodes.len())));
+                nodes.push((next, next_cost));
+            }
+        }
+    }
+
+    search
+}
+
+/* -------------------------------------------------------------------------- */
+
+/// All nodes reachable from `start`, including `start` itself.
+pub fn flood_fill<N, I>(start: N, mut successors: impl FnMut(&N) -> I) -> HashSet<N>
+where
+    N: Clone + Eq + Hash,
+    I: IntoIterator<Item = N>,
+{
+    let mut visited = HashSet::from([start.clone()]);
+    let mut stack = vec![start];
+
+    while let Some(node) = stack.pop() {
+        for next in successors(&node) {
+            if visited.insert(next.clone()) {
+                stack.push(next);
+            }
+        }
+    }
+
+    visited
+}
+
+/// Groups `nodes` into connected components, in the order their first node appears in `nodes`.
+/// The graph is treated as undirected, so `successors` should be symmetric.
+/// Nodes reachable from `nodes` but not part of it are included in the components as well.
+pub fn connected_components<N, I>(
+    nodes: impl IntoIterator<Item = N>,
+    mut successors: impl FnMut(&N) -> I,
+) -> Vec<HashSet<N>>
+where
+    N: Clone + Eq + Hash,
+    I: IntoIterator<Item = N>,
+{
+    let mut components: Vec<HashSet<N>> = vec![];
+    let mut seen = HashSet::new();
+
+    for node in nodes {
+        if seen.contains(&node) {
+            continue;
+        }
+        let component = flood_fill(node, &mut successors);
+        seen.extend(component.iter().cloned());
+        components.push(component);
+    }
+
+    components
+}
+
+/* -------------------------------------------------------------------------- */
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use std::collections::{HashMap, HashSet};
+
+    use super::{astar, bfs, connected_components, dfs, dijkstra, flood_fill};
+    use crate::grid::Grid;
+    use crate::point::Point;
+
+    const MAZE: &str = "\
+S...#
+.##.#
+.#..#
+.#.##
+...#E";
+
+    fn open_neighbors(grid: &Grid<char>, point: Point) -> Vec<Point> {
+        grid.neighbors4(point).filter(|&p| grid[p] != '#').collect()
+    }
+
+    #[test]
+    fn bfs_finds_shortest_paths() {
+        let grid: Grid<char> = MAZE.parse().unwrap();
+        let start = grid.find(&'S').unwrap();
+        let search = bfs(start, |&p| open_neighbors(&grid, p), |_| false);
+
+        assert_eq!(search.target(), None);
+        assert_eq!(search.distance(&Point::new(3, 0)), Some(3));
+        assert_eq!(search.distance(&Point::new(2, 3)), Some(7));
+        assert_eq!(search.distance(&grid.find(&'E').unwrap()), None);
+
+        let path = search.path_to(&Point::new(2, 2)).unwrap();
+        assert_eq!(path.first(), Some(&start));
+        assert_eq!(path.len(), 7);
+        assert!(path.windows(2).all(|w| w[0].manhattan_distance(&w[1]) == 1));
+    }
+
+    #[test]
+    fn bfs_stops_at_goal() {
+        let search = bfs(0_u32, |&n| [n + 1, n * 2], |&n| n == 10);
+        assert_eq!(search.target(), Some(&10));
+        assert_eq!(search.target_distance(), Some(5));
+        assert_eq!(search.target_path().unwrap(), [0, 1, 2, 4, 5, 10]);
+    }
+
+    #[test]
+    fn dfs_visits_depth_first() {
+        let graph = HashMap::from([
+            ("a", vec!["b", "c"]),
+            ("b", vec!["d"]),
+            ("c", vec!["d"]),
+            ("d", vec![]),
+        ]);
+        let search = dfs("a", |n| graph[n].clone(), |_| false);
+        assert_eq!(search.distance(&"d"), Some(2));
+        assert_eq!(search.path_to(&"d").unwrap(), ["a", "b", "d"]);
+        assert_eq!(search.distances().len(), 4);
+
+        let search = dfs("a", |n| graph[n].clone(), |&n| n == "b");
+        assert_eq!(search.target_path().unwrap(), ["a", "b"]);
+        assert!(!search.contains(&"d"));
+    }
+
+    #[test]
+    fn dijkstra_uses_costs() {
+        let graph = HashMap::from([
+            ('a', vec![('b', 7), ('c', 1)]),
+            ('b', vec![('d', 1)]),
+            ('c', vec![('b', 2), ('d', 10)]),
+            ('d', vec![]),
+        ]);
+        let search = dijkstra('a', |n| graph[n].clone(), |&n| n == 'd');
+        assert_eq!(search.target_distance(), Some(4));
+        assert_eq!(search.target_path().unwrap(), ['a', 'c', 'b', 'd']);
+        assert_eq!(search.predecessor(&'b'), Some(&'c'));
+        assert_eq!(search.predecessor(&'a'), None);
+    }
+
+    #[test]
+    fn astar_matches_dijkstra() {
+        let grid = Grid::from_fn(12, 12, |p| (p.x * 7 + p.y * 13) % 9 + 1);
+        let goal = Point::new(11, 11);
+        let successors = |&p: &Point| -> Vec<(Point, i32)> {
+            grid.neighbors4(p).map(|n| (n, grid[n])).collect()
+        };
+
+        let expected = dijkstra(Point::new(0, 0), successors, |&p| p == goal);
+        let search = astar(
+            Point::new(0, 0),
+            successors,
+            |p| p.manhattan_distance(&goal) as i32,
+            |&p| p == goal,
+        );
+        assert_eq!(search.target_distance(), expected.target_distance());
+
+        let path = search.target_path().unwrap();
+        let cost: i32 = path.iter().skip(1).map(|&p| grid[p]).sum();
+        assert_eq!(Some(cost), search.target_distance());
+    }
+
+    #[test]
+    fn fills_and_groups() {
+        let grid: Grid<char> = "aab\nbab\nbba".parse().unwrap();
+        let same_neighbors = |&p: &Point| -> Vec<Point> {
+            grid.neighbors4(p).filter(|&n| grid[n] == grid[p]).collect()
+        };
+
+        let region = flood_fill(Point::new(0, 0), same_neighbors);
+        assert_eq!(
+            region,
+            HashSet::from([Point::new(0, 0), Point::new(1, 0), Point::new(1, 1)])
+        );
+
+        let components = connected_components(grid.points(), same_neighbors);
+        let sizes: Vec<usize> = components.iter().map(HashSet::len).collect();
+        assert_eq!(sizes, [3, 2, 3, 1]);
+    }
+}
@@ -0,0 +1,212 @@
+//! Extrapolation of sequences whose repeated differences end in a row of zeros
+//!
+//! Such a sequence is made of the values of a polynomial at `0, 1, 2, ...`.
+//! [`Polynomial::fit`] keeps only the first value of every row of differences.
+//! The value at any index, before or after the known values, is then a sum of binomial coefficients:
+//! `a(x) = Σ C(x, j) · Δʲa(0)`. No row of the difference pyramid has to be stored or extended.
+//!
+//! ```
+//! use advent_of_code::sequence::{Polynomial, SequenceError};
+//!
+//! let squares = Polynomial::fit(&[0_i64, 1, 4, 9, 16]).unwrap();
+//! assert_eq!(squares.degree(), 2);
+//! assert_eq!(squares.forward(1), Some(25));
+//! assert_eq!(squares.backward(3), Some(9));
+//! assert_eq!(squares.value_at(1_000_000), Some(1_000_000_000_000));
+//!
+//! // the differences of 1, 2, 4, 8 never become zero.
+//! assert_eq!(Polynomial::fit(&[1_i64, 2, 4, 8]), Err(SequenceError::NoZeroRow));
+//! ```
+
+use std::error::Error;
+use std::fmt::Display;
+
+use crate::math::{self, SignedInteger};
+
+/// A polynomial given by its values at `0, 1, 2, ...`, stored as the first value of every row of differences.
+#[derive(PartialEq, Eq, Hash, Clone, Debug)]
+pub struct Polynomial<T> {
+    /// `Δʲa(0)` for every row `j` above the row of zeros
+    differences: Vec<T>,
+    /// The number of values the polynomial was fitted to
+    len: T,
+}
+
+impl<T: SignedInteger> Polynomial<T> {
+    /// Computes the differences of `values` until they are all zero.
+    /// Only a single row of differences is kept in memory, it is updated in place.
+    pub fn fit(values: &[T]) -> Result<Self, SequenceError> {
+        if values.is_empty() {
+            return Err(SequenceError::Empty);
+        }
+
+        let mut row = values.to_vec();
+        let mut differences = vec![];
+        for len in (1..=row.len()).rev() {
+            if row[..len].iter().all(|&value| value == T::ZERO) {
+                let len = values
+                    .iter()
+                    .try_fold(T::ZERO, |len, _| len.checked_add(T::ONE));
+                return Ok(Polynomial {
+                    differences,
+                    len: len.ok_or(SequenceError::Overflow)?,
+                });
+            }
+
+            differences.push(row[0]);
+            for idx in 0..len - 1 {
+                row[idx] = row[idx + 1]
+                    .checked_sub(row[idx])
+                    .ok_or(SequenceError::Overflow)?;
+            }
+        }
+
+        Err(SequenceError::NoZeroRow)
+    }
+
+    /// The degree of the polynomial, `0` for constant and all-zero sequences
+    pub fn degree(&self) -> usize {
+        self.differences.len().saturating_sub(1)
+    }
+
+    /// The value at `index`, where the fitted values are at the indices `0, 1, 2, ...`.
+    /// Negative indices lie before the first value. Returns `None` if a calculation overflows.
+    pub fn value_at(&self, index: T) -> Option<T> {
+        let mut value = T::ZERO;
+        // the generalized binomial coefficient C(index, j), which is also defined for negative indices.
+        let mut binomial = T::ONE;
+        let mut j = T::ZERO;
+
+        for (row, &difference) in self.differences.iter().enumerate() {
+            if row > 0 {
+                // C(x, j + 1) = C(x, j) · (x - j) / (j + 1), the division is always exact.
+                // dividing before multiplying keeps the intermediate product as small as C(x, j + 1).
+                let factor = index.checked_sub(j)?;
+                j = j.checked_add(T::ONE)?;
+                let common = math::gcd(binomial, j);
+                binomial = (binomial / common).checked_mul(factor / (j / common))?;
+            }
+            value = value.checked_add(binomial.checked_mul(difference)?)?;
+        }
+
+        Some(value)
+    }
+
+    /// The value `steps` places after the last fitted value
+    pub fn forward(&self, steps: T) -> Option<T> {
+        self.value_at(self.len.checked_sub(T::ONE)?.checked_add(steps)?)
+    }
+
+    /// The value `steps` places before the first fitted value
+    pub fn backward(&self, steps: T) -> Option<T> {
+        self.value_at(T::ZERO.checked_sub(steps)?)
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+/// An error which can be returned when fitting a [`Polynomial`].
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum SequenceError {
+    /// There are no values to fit.
+    Empty,
+    /// The last row of differences has a single value that is not zero, so the next value is not determined.
+    NoZeroRow,
+    /// A difference does not fit into the value type.
+    Overflow,
+}
+
+impl Error for SequenceError {}
+
+impl Display for SequenceError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SequenceError::Empty => f.write_str("cannot extrapolate an empty sequence"),
+            SequenceError::NoZeroRow => {
+                f.write_str("the differences of the sequence never become all zero")
+            }
+            SequenceError::Overflow => f.write_str("the differences of the sequence overflow"),
+        }
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::{Polynomial, SequenceError};
+    use crate::testing::Rng;
+
+    fn evaluate(coefficients: &[i64], x: i64) -> i64 {
+        coefficients.iter().rev().fold(0, |value, &c| value * x + c)
+    }
+
+    #[test]
+    fn extrapolates_puzzle_examples() {
+        let sequence = Polynomial::fit(&[10, 13, 16, 21, 30, 45]).unwrap();
+        assert_eq!(sequence.degree(), 3);
+        assert_eq!(sequence.forward(1), Some(68));
+        assert_eq!(sequence.backward(1), Some(5));
+
+        let sequence = Polynomial::fit(&[0, 3, 6, 9, 12, 15]).unwrap();
+        assert_eq!(sequence.forward(1), Some(18));
+        assert_eq!(sequence.backward(1), Some(-3));
+        assert_eq!(sequence.forward(0), Some(15));
+        assert_eq!(sequence.backward(0), Some(0));
+    }
+
+    #[test]
+    fn detects_sequences_without_zero_row() {
+        assert_eq!(Polynomial::<i32>::fit(&[]), Err(SequenceError::Empty));
+        assert_eq!(Polynomial::fit(&[3]), Err(SequenceError::NoZeroRow));
+        assert_eq!(Polynomial::fit(&[1, 2, 4]), Err(SequenceError::NoZeroRow));
+
+        let zeros = Polynomial::fit(&[0, 0, 0]).unwrap();
+        assert_eq!((zeros.degree(), zeros.forward(5)), (0, Some(0)));
+        let constant = Polynomial::fit(&[7, 7]).unwrap();
+        assert_eq!(constant.backward(100), Some(7));
+    }
+
+    #[test]
+    fn reports_overflow() {
+        assert_eq!(
+            Polynomial::fit(&[i64::MIN, i64::MAX]),
+            Err(SequenceError::Overflow)
+        );
+
+        let sequence = Polynomial::fit(&[0_i64, 1, 4, 9]).unwrap();
+        assert_eq!(
+            sequence.value_at(3_000_000_000),
+            Some(9_000_000_000_000_000_000)
+        );
+        assert_eq!(sequence.value_at(4_000_000_000), None);
+
+        // C(x, 2): only x · (x - 1) overflows, the value itself fits.
+        let pairs = Polynomial::fit(&[0_i64, 0, 1, 3]).unwrap();
+        assert_eq!(
+            pairs.value_at(4_000_000_000),
+            Some(7_999_999_998_000_000_000)
+        );
+        assert_eq!(sequence.backward(i64::MAX), None);
+    }
+
+    #[test]
+    fn matches_direct_evaluation() {
+        let mut rng = Rng::new(0x5851_f42d_4c95_7f2d);
+        for _ in 0..500 {
+            let degree = rng.below(6) as usize;
+            let coefficients: Vec<i64> = (0..=degree).map(|_| rng.below(21) as i64 - 10).collect();
+            let len = degree + 2 + rng.below(4) as usize;
+            let values: Vec<i64> = (0..len as i64)
+                .map(|x| evaluate(&coefficients, x))
+                .collect();
+
+            let polynomial = Polynomial::fit(&values).unwrap();
+            assert!(polynomial.degree() <= degree);
+            for _ in 0..10 {
+                let x = rng.below(200) as i64 - 100;
+                assert_eq!(polynomial.value_at(x), Some(evaluate(&coefficients, x)));
+            }
+        }
+    }
+}
@@ -0,0 +1,406 @@
+//! A sparse 2D grid for unbounded or mostly empty planes
+//!
+//! Only occupied cells are stored. Every other position reads as the default value of the grid,
+//! and the bounding box of the occupied cells is kept up to date for rendering.
+//!
+//! ```
+//! use advent_of_code::point::Point;
+//! use advent_of_code::sparse_grid::SparseGrid;
+//!
+//! let mut grid = SparseGrid::with_default('.');
+//! grid.insert(Point::new(-2, 0), '#');
+//! grid.insert(Point::new(1, 1), '#');
+//!
+//! assert_eq!(grid[Point::new(100, 100)], '.');
+//! assert_eq!(grid.bounds().unwrap().width(), 4);
+//! assert_eq!(grid.to_string(), "#...\n...#");
+//! ```
+
+use std::collections::HashMap;
+use std::fmt::Display;
+use std::ops::Index;
+
+use crate::grid::Grid;
+use crate::point::Point;
+
+/// An inclusive, axis-aligned rectangle of points.
+#[derive(PartialEq, Eq, Hash, Clone, Copy, Debug)]
+pub struct Bounds {
+    pub min: Point,
+    pub max: Point,
+}
+
+impl Bounds {
+    /// The bounds of a single point
+    pub fn new(point: Point) -> Self {
+        Bounds {
+            min: point,
+            max: point,
+        }
+    }
+
+    /// The smallest bounds that contain all `points`, `None` if there are no points
+    pub fn of(points: impl IntoIterator<Item = Point>) -> Option<Self> {
+        let mut points = points.into_iter();
+        let first = Bounds::new(points.next()?);
+        Some(points.fold(first, Bounds::extend))
+    }
+
+    /// The smallest bounds that contain these bounds and `point`
+    #[must_use]
+    pub fn extend(self, point: Point) -> Self {
+        Bounds {
+            min: Point::new(self.min.x.min(point.x), self.min.y.min(point.y)),
+            max: Point::new(self.max.x.max(point.x), self.max.y.max(point.y)),
+        }
+    }
+
+    pub fn width(&self) -> usize {
+        self.max.x.abs_diff(self.min.x) as usize + 1
+    }
+
+    pub fn height(&self) -> usize {
+        self.max.y.abs_diff(self.min.y) as usize + 1
+    }
+
+    pub fn contains(&self, point: Point) -> bool {
+        (self.min.x..=self.max.x).contains(&point.x) && (self.min.y..=self.max.y).contains(&point.y)
+    }
+
+    /// Checks if `point` lies on the outer edge of the bounds
+    pub fn is_on_edge(&self, point: Point) -> bool {
+        self.contains(point)
+            && (point.x == self.min.x
+                || point.x == self.max.x
+                || point.y == self.min.y
+                || point.y == self.max.y)
+    }
+
+    /// All points within the bounds in reading order
+    pub fn points(&self) -> impl Iterator<Item = Point> {
+        let Bounds { min, max } = *self;
+        (min.y..=max.y).flat_map(move |y| (min.x..=max.x).map(move |x| Point::new(x, y)))
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+#[derive(Clone, Debug)]
+pub struct SparseGrid<T> {
+    cells: HashMap<Point, T>,
+    default: T,
+    bounds: Option<Bounds>,
+}
+
+impl<T: Default> SparseGrid<T> {
+    /// Creates an empty grid that reads as `T::default()` everywhere.
+    pub fn new() -> Self {
+        Self::with_default(T::default())
+    }
+}
+
+impl<T: Default> Default for SparseGrid<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> SparseGrid<T> {
+    /// Creates an empty grid that reads as `default` everywhere.
+    pub fn with_default(default: T) -> Self {
+        SparseGrid {
+            cells: HashMap::new(),
+            default,
+            bounds: None,
+        }
+    }
+
+    /// Parses one cell per character, one row per line, with the top left character at `(0, 0)`.
+    /// Characters for which `func` returns `None` are left empty.
+    pub fn parse_with(input: &str, default: T, mut func: impl FnMut(char) -> Option<T>) -> Self {
+        let mut grid = Self::with_default(default);
+        for (y, line) in input.lines().enumerate() {
+            for (x, c) in line.chars().enumerate() {
+                if let Some(cell) = func(c) {
+                    let point = Point::try_from((x, y)).expect("input should fit into i32");
+                    grid.insert(point, cell);
+                }
+            }
+        }
+        grid
+    }
+
+    /// Converts the cells of a dense grid for which `func` returns `Some`.
+    pub fn from_grid<U>(grid: &Grid<U>, default: T, mut func: impl FnMut(&U) -> Option<T>) -> Self {
+        let mut sparse = Self::with_default(default);
+        for (point, cell) in grid.iter() {
+            if let Some(cell) = func(cell) {
+                sparse.insert(point, cell);
+            }
+        }
+        sparse
+    }
+
+    pub fn default_value(&self) -> &T {
+        &self.default
+    }
+
+    /// The number of occupied cells
+    pub fn len(&self) -> usize {
+        self.cells.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cells.is_empty()
+    }
+
+    /// Checks if the cell at `point` is occupied
+    pub fn contains(&self, point: Point) -> bool {
+        self.cells.contains_key(&point)
+    }
+
+    /// The cell at `point`, `None` if it's not occupied
+    pub fn get(&self, point: Point) -> Option<&T> {
+        self.cells.get(&point)
+    }
+
+    /// The cell at `point`, `None` if it's not occupied
+    pub fn get_mut(&mut self, point: Point) -> Option<&mut T> {
+        self.cells.get_mut(&point)
+    }
+
+    /// The cell at `point`, occupying it with the default value first if needed
+    pub fn get_mut_or_default(&mut self, point: Point) -> &mut T
+    where
+        T: Clone,
+    {
+        self.extend_bounds(point);
+        self.cells
+            .entry(point)
+            .or_insert_with(|| self.default.clone())
+    }
+
+    /// Occupies the cell at `point`, returns the previous value if it was occupied.
+    pub fn insert(&mut self, point: Point, value: T) -> Option<T> {
+        self.extend_bounds(point);
+        self.cells.insert(point, value)
+    }
+
+    /// Clears the cell at `point`, returns its value if it was occupied.
+    pub fn remove(&mut self, point: Point) -> Option<T> {
+        let value = self.cells.remove(&point)?;
+        // only cells on the edge can shrink the bounding box.
+        if self.bounds.is_some_and(|bounds| bounds.is_on_edge(point)) {
+            self.bounds = Bounds::of(self.cells.keys().copied());
+        }
+        Some(value)
+    }
+
+    /// The bounding box of all occupied cells, `None` if the grid is empty
+    pub fn bounds(&self) -> Option<Bounds> {
+        self.bounds
+    }
+
+    /// The occupied cells in arbitrary order
+    pub fn iter(&self) -> impl Iterator<Item = (Point, &T)> {
+        self.cells.iter().map(|(&point, cell)| (point, cell))
+    }
+
+    /// The occupied positions in arbitrary order
+    pub fn points(&self) -> impl Iterator<Item = Point> + '_ {
+        self.cells.keys().copied()
+    }
+
+    /// Renders the bounding box of the occupied cells, one line per row.
+    /// `func` is called with every position and its cell, `None` for empty cells.
+    pub fn render(&self, mut func: impl FnMut(Point, Option<&T>) -> char) -> String {
+        let Some(bounds) = self.bounds else {
+            return String::new();
+        };
+
+        let mut output = String::with_capacity((bounds.width() + 1) * bounds.height());
+        for y in bounds.min.y..=bounds.max.y {
+            if y > bounds.min.y {
+                output.push('\n');
+            }
+            for x in bounds.min.x..=bounds.max.x {
+                let point = Point::new(x, y);
+                output.push(func(point, self.get(point)));
+            }
+        }
+        output
+    }
+
+    /// Copies the bounding box of the occupied cells into a dense grid.
+    /// Returns the grid together with the position of its top left cell.
+    pub fn to_grid(&self) -> (Grid<T>, Point)
+    where
+        T: Clone,
+    {
+        match self.bounds {
+            Some(bounds) => {
+                let grid = Grid::from_fn(bounds.width(), bounds.height(), |p| {
+                    self[p + bounds.min].clone()
+                });
+                (grid, bounds.min)
+            }
+            None => (Grid::new(0, 0, self.default.clone()), Point::new(0, 0)),
+        }
+    }
+
+    fn extend_bounds(&mut self, point: Point) {
+        self.bounds = Some(match self.bounds {
+            Some(bounds) => bounds.extend(point),
+            None => Bounds::new(point),
+        });
+    }
+}
+
+/// Reads the default value for empty cells.
+impl<T> Index<Point> for SparseGrid<T> {
+    type Output = T;
+
+    fn index(&self, point: Point) -> &T {
+        self.get(point).unwrap_or(&self.default)
+    }
+}
+
+/// Two grids are equal if they read the same everywhere.
+impl<T: PartialEq> PartialEq for SparseGrid<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.default == other.default
+            && self.iter().all(|(point, cell)| other[point] == *cell)
+            && other.iter().all(|(point, cell)| self[point] == *cell)
+    }
+}
+
+impl<T: Eq> Eq for SparseGrid<T> {}
+
+impl<T: Default> FromIterator<(Point, T)> for SparseGrid<T> {
+    fn from_iter<I: IntoIterator<Item = (Point, T)>>(iter: I) -> Self {
+        let mut grid = Self::new();
+        grid.extend(iter);
+        grid
+    }
+}
+
+impl<T> Extend<(Point, T)> for SparseGrid<T> {
+    fn extend<I: IntoIterator<Item = (Point, T)>>(&mut self, iter: I) {
+        for (point, cell) in iter {
+            self.insert(point, cell);
+        }
+    }
+}
+
+/// Renders the bounding box of the occupied cells, empty cells show the default value.
+impl<T: Display> Display for SparseGrid<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let Some(bounds) = self.bounds else {
+            return Ok(());
+        };
+
+        for y in bounds.min.y..=bounds.max.y {
+            if y > bounds.min.y {
+                writeln!(f)?;
+            }
+            for x in bounds.min.x..=bounds.max.x {
+                write!(f, "{}", self[Point::new(x, y)])?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::{Bounds, SparseGrid};
+    use crate::grid::Grid;
+    use crate::point::Point;
+
+    #[test]
+    fn defaults() {
+        let mut grid: SparseGrid<u32> = SparseGrid::new();
+        assert!(grid.is_empty());
+        assert_eq!(grid[Point::new(-5, 7)], 0);
+        *grid.get_mut_or_default(Point::new(-5, 7)) += 3;
+        assert_eq!(grid[Point::new(-5, 7)], 3);
+        assert_eq!(grid.get(Point::new(0, 0)), None);
+        assert_eq!(grid.len(), 1);
+    }
+
+    #[test]
+    fn tracks_bounds() {
+        let mut grid = SparseGrid::with_default(' ');
+        assert_eq!(grid.bounds(), None);
+        grid.insert(Point::new(1, 1), 'a');
+        grid.insert(Point::new(-3, 2), 'b');
+        grid.insert(Point::new(0, -1), 'c');
+        let bounds = grid.bounds().unwrap();
+        assert_eq!(bounds.min, Point::new(-3, -1));
+        assert_eq!(bounds.max, Point::new(1, 2));
+        assert_eq!((bounds.width(), bounds.height()), (5, 4));
+
+        assert_eq!(grid.remove(Point::new(-3, 2)), Some('b'));
+        assert_eq!(
+            grid.bounds(),
+            Some(Bounds {
+                min: Point::new(0, -1),
+                max: Point::new(1, 1)
+            })
+        );
+        grid.remove(Point::new(1, 1));
+        grid.remove(Point::new(0, -1));
+        assert_eq!(grid.bounds(), None);
+    }
+
+    #[test]
+    fn bounds_helpers() {
+        let bounds = Bounds::of([Point::new(2, 3), Point::new(0, 4)]).unwrap();
+        assert!(bounds.contains(Point::new(1, 3)));
+        assert!(!bounds.contains(Point::new(3, 3)));
+        assert!(bounds.is_on_edge(Point::new(0, 3)));
+        assert!(!Bounds::of([Point::new(0, 0), Point::new(2, 2)])
+            .unwrap()
+            .is_on_edge(Point::new(1, 1)));
+        assert_eq!(bounds.points().count(), 6);
+        assert_eq!(Bounds::of([]), None);
+    }
+
+    #[test]
+    fn parses_and_renders() {
+        let input = "#..\n..#\n";
+        let grid = SparseGrid::parse_with(input, '.', |c| (c == '#').then_some('#'));
+        assert_eq!(grid.len(), 2);
+        assert_eq!(grid.to_string(), input.trim_end());
+        assert_eq!(
+            grid.render(|_, cell| if cell.is_some() { 'X' } else { ' ' }),
+            "X  \n  X"
+        );
+        assert_eq!(SparseGrid::<char>::new().to_string(), "");
+    }
+
+    #[test]
+    fn converts_from_and_to_dense_grids() {
+        let dense: Grid<char> = ".#.\n#..".parse().unwrap();
+        let sparse = SparseGrid::from_grid(&dense, false, |&c| (c == '#').then_some(true));
+        assert_eq!(sparse.len(), 2);
+
+        let (grid, origin) = sparse.to_grid();
+        assert_eq!(origin, Point::new(0, 0));
+        assert_eq!(
+            grid.map(|&c| if c { '#' } else { '.' }).to_string(),
+            ".#\n#."
+        );
+    }
+
+    #[test]
+    fn equality_ignores_explicit_defaults() {
+        let a: SparseGrid<u8> = [(Point::new(0, 0), 1), (Point::new(1, 0), 0)]
+            .into_iter()
+            .collect();
+        let b: SparseGrid<u8> = [(Point::new(0, 0), 1)].into_iter().collect();
+        assert_eq!(a, b);
+    }
+}
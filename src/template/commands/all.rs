@@ -2,11 +2,32 @@ use std::io;
 
 use crate::template::{
     readme_benchmarks::{self, Timings},
-    ANSI_BOLD, ANSI_ITALIC, ANSI_RESET,
+    report, ANSI_BOLD, ANSI_ITALIC, ANSI_RESET,
 };
 use crate::{all_days, Day};
 
-pub fn handle(is_release: bool, is_timed: bool) {
+pub fn handle(
+    is_release: bool,
+    is_timed: bool,
+    is_mem: bool,
+    is_dry_run: bool,
+    report: Option<report::Target>,
+) {
+    if report.is_some() && !is_timed {
+        eprintln!("The --report option requires --time.");
+        std::process::exit(1);
+    }
+
+    if is_mem && !is_timed {
+        eprintln!("The --mem option of `all` requires --time, memory usage is collected with the timings.");
+        std::process::exit(1);
+    }
+
+    if is_dry_run && !(is_release && is_timed) {
+        eprintln!("The --dry-run option requires --release --time.");
+        std::process::exit(1);
+    }
+
     let mut timings: Vec<Timings> = vec![];
 
     all_days().for_each(|day| {
@@ -17,12 +38,15 @@ pub fn handle(is_release: bool, is_timed: bool) {
         println!("{ANSI_BOLD}Day {day}{ANSI_RESET}");
         println!("------");
 
-        let output = child_commands::run_solution(day, is_timed, is_release).unwrap();
+        let output = child_commands::run_solution(day, is_timed, is_release, is_mem).unwrap();
 
         if output.is_empty() {
             println!("Not solved.");
         } else {
-            let val = child_commands::parse_exec_time(&output, day);
+            let mut val = child_commands::parse_exec_time(&output, day);
+            if is_mem {
+                child_commands::parse_mem_usage(&output, &mut val);
+            }
             timings.push(val);
         }
     });
@@ -32,11 +56,26 @@ pub fn handle(is_release: bool, is_timed: bool) {
 
         println!("\n{ANSI_BOLD}Total:{ANSI_RESET} {ANSI_ITALIC}{total_millis:.2}ms{ANSI_RESET}");
 
-        if is_release {
+        if let Some(target) = report {
+            let environment = report::Environment::detect(is_release);
+            match report::write(&target, &timings, total_millis, &environment) {
+                Ok(()) => println!(
+                    "Successfully wrote benchmark report to \"{}\".",
+                    target.path
+                ),
+                Err(e) => eprintln!("Failed to write benchmark report: {e}"),
+            }
+        } else if is_release && is_dry_run {
+            match readme_benchmarks::preview(timings, total_millis) {
+                Ok(diff) if diff.is_empty() => println!("README benchmarks are up to date."),
+                Ok(diff) => println!("\nChanges to README (dry run, nothing was written):\n{diff}"),
+                Err(e) => eprintln!("Failed to preview readme benchmarks: {e}"),
+            }
+        } else if is_release {
             match readme_benchmarks::update(timings, total_millis) {
                 Ok(()) => println!("Successfully updated README with benchmarks."),
-                Err(_) => {
-                    eprintln!("Failed to update readme with benchmarks.");
+                Err(e) => {
+                    eprintln!("Failed to update readme with benchmarks: {e}");
                 }
             }
         }
@@ -65,6 +104,7 @@ pub fn get_path_for_bin(day: Day) -> String {
 /// This module encapsulates interaction with these binaries, both invoking them as well as parsing the timing output.
 mod child_commands {
     use super::{get_path_for_bin, Error};
+    use crate::template::readme_benchmarks::{self, Measurement};
     use crate::Day;
     use std::{
         io::{BufRead, BufReader},
@@ -74,7 +114,12 @@ mod child_commands {
     };
 
     /// Run the solution bin for a given day
-    pub fn run_solution(day: Day, is_timed: bool, is_release: bool) -> Result<Vec<String>, Error> {
+    pub fn run_solution(
+        day: Day,
+        is_timed: bool,
+        is_release: bool,
+        is_mem: bool,
+    ) -> Result<Vec<String>, Error> {
         // skip command invocation for days that have not been scaffolded yet.
         if !Path::new(&get_path_for_bin(day)).exists() {
             return Ok(vec![]);
@@ -87,10 +132,20 @@ mod child_commands {
             args.push("--release");
         }
 
+        // mirror `--time` and `--mem` flags to child invocations.
+        let mut child_args = vec![];
+
         if is_timed {
-            // mirror `--time` flag to child invocations.
+            child_args.push("--time");
+        }
+
+        if is_mem {
+            child_args.push("--mem");
+        }
+
+        if !child_args.is_empty() {
             args.push("--");
-            args.push("--time");
+            args.append(&mut child_args);
         }
 
         // spawn child command with piped stdout/stderr.
@@ -128,8 +183,12 @@ mod child_commands {
     pub fn parse_exec_time(output: &[String], day: Day) -> super::Timings {
         let mut timings = super::Timings {
             day,
+            parse: None,
             part_1: None,
             part_2: None,
+            parse_mem: None,
+            part_1_mem: None,
+            part_2_mem: None,
             total_nanos: 0_f64,
         };
 
@@ -140,48 +199,78 @@ mod child_commands {
                     return None;
                 }
 
-                let Some((timing_str, nanos)) = parse_time(l) else {
+                let (Some((timing_str, nanos)), Some(samples)) = (parse_time(l), parse_samples(l))
+                else {
                     eprintln!("Could not parse timings from line: {l}");
                     return None;
                 };
 
                 let part = l.split(':').next()?;
-                Some((part, timing_str, nanos))
+                let measurement = Measurement {
+                    time: timing_str.into(),
+                    nanos,
+                    samples,
+                };
+                Some((part, measurement))
             })
-            .for_each(|(part, timing_str, nanos)| {
-                if part.contains("Part 1") {
-                    timings.part_1 = Some(timing_str.into());
+            .for_each(|(part, measurement)| {
+                timings.total_nanos += measurement.nanos;
+
+                if part.contains("Parse") {
+                    timings.parse = Some(measurement);
+                } else if part.contains("Part 1") {
+                    timings.part_1 = Some(measurement);
                 } else if part.contains("Part 2") {
-                    timings.part_2 = Some(timing_str.into());
+                    timings.part_2 = Some(measurement);
                 }
-
-                timings.total_nanos += nanos;
             });
 
         timings
     }
 
-    fn parse_to_float(s: &str, postfix: &str) -> Option<f64> {
-        s.split(postfix).next()?.parse().ok()
+    pub fn parse_mem_usage(output: &[String], timings: &mut super::Timings) {
+        for line in output {
+            let Some(mem_str) = parse_mem(line) else {
+                continue;
+            };
+
+            let part = line.split(':').next().unwrap_or_default();
+            if part.contains("Parse") {
+                timings.parse_mem = Some(mem_str.into());
+            } else if part.contains("Part 1") {
+                timings.part_1_mem = Some(mem_str.into());
+            } else if part.contains("Part 2") {
+                timings.part_2_mem = Some(mem_str.into());
+            }
+        }
+    }
+
+    fn parse_mem(line: &str) -> Option<&str> {
+        let (_, mem_str) = line.rsplit_once("[mem: ")?;
+        mem_str.strip_suffix(']').map(str::trim)
+    }
+
+    fn parse_samples(line: &str) -> Option<u128> {
+        line.split(" samples)")
+            .next()?
+            .split('@')
+            .next_back()?
+            .trim()
+            .parse()
+            .ok()
     }
 
     fn parse_time(line: &str) -> Option<(&str, f64)> {
-        // for possible time formats, see: https://github.com/rust-lang/rust/blob/1.64.0/library/core/src/time.rs#L1176-L1200
         let str_timing = line
             .split(" samples)")
             .next()?
             .split('(')
-            .last()?
+            .next_back()?
             .split('@')
             .next()?
             .trim();
 
-        let parsed_timing = match str_timing {
-            s if s.contains("ns") => s.split("ns").next()?.parse::<f64>().ok(),
-            s if s.contains("µs") => parse_to_float(s, "µs").map(|x| x * 1000_f64),
-            s if s.contains("ms") => parse_to_float(s, "ms").map(|x| x * 1_000_000_f64),
-            s => parse_to_float(s, "s").map(|x| x * 1_000_000_000_f64),
-        }?;
+        let parsed_timing = readme_benchmarks::parse_duration(str_timing)?;
 
         Some((str_timing, parsed_timing))
     }
@@ -202,7 +291,7 @@ mod child_commands {
 
     #[cfg(feature = "test_lib")]
     mod tests {
-        use super::parse_exec_time;
+        use super::{parse_exec_time, parse_mem_usage};
 
         use crate::day;
 
@@ -217,8 +306,23 @@ mod child_commands {
                 day!(1),
             );
             assert_approx_eq!(res.total_nanos, 74130074.13_f64);
-            assert_eq!(res.part_1.unwrap(), "74.13ns");
-            assert_eq!(res.part_2.unwrap(), "74.13ms");
+            assert_eq!(res.part_1.unwrap().time, "74.13ns");
+            assert_eq!(res.part_2.unwrap().time, "74.13ms");
+        }
+
+        #[test]
+        fn test_samples() {
+            let res = parse_exec_time(
+                &[
+                    "Part 1: 0 (74.13ns @ 100000 samples)".into(),
+                    "Part 2: 10 (74.13ms @ 99999 samples) [mem: 1 allocs, 8 B, 8 B peak]".into(),
+                ],
+                day!(1),
+            );
+            let part_1 = res.part_1.unwrap();
+            assert_eq!(part_1.samples, 100000);
+            assert_approx_eq!(part_1.nanos, 74.13_f64);
+            assert_eq!(res.part_2.unwrap().samples, 99999);
         }
 
         #[test]
@@ -232,8 +336,8 @@ mod child_commands {
                 day!(1),
             );
             assert_approx_eq!(res.total_nanos, 2100000000_f64);
-            assert_eq!(res.part_1.unwrap(), "2s");
-            assert_eq!(res.part_2.unwrap(), "100ms");
+            assert_eq!(res.part_1.unwrap().time, "2s");
+            assert_eq!(res.part_2.unwrap().time, "100ms");
         }
 
         #[test]
@@ -250,5 +354,38 @@ mod child_commands {
             assert_eq!(res.part_1.is_none(), true);
             assert_eq!(res.part_2.is_none(), true);
         }
+
+        #[test]
+        fn test_parse_phase() {
+            let res = parse_exec_time(
+                &[
+                    "Parse: ✔ (1ms @ 100 samples)".into(),
+                    "Part 1: 0 (2ms @ 100 samples)".into(),
+                    "Part 2: 10 (3ms @ 100 samples)".into(),
+                    "".into(),
+                ],
+                day!(1),
+            );
+            assert_approx_eq!(res.total_nanos, 6000000_f64);
+            assert_eq!(res.parse.unwrap().time, "1ms");
+            assert_eq!(res.part_1.unwrap().time, "2ms");
+            assert_eq!(res.part_2.unwrap().time, "3ms");
+        }
+
+        #[test]
+        fn test_mem_usage() {
+            let output = [
+                "Part 1: 42 (74.13ns @ 100000 samples) [mem: 3 allocs, 1.5 KiB, 1.0 KiB peak]"
+                    .into(),
+                "Part 2: 10 (2.1ms) [mem: 0 allocs, 0 B, 0 B peak]".into(),
+                "".into(),
+            ];
+            let mut res = parse_exec_time(&output, day!(1));
+            parse_mem_usage(&output, &mut res);
+            assert_approx_eq!(res.total_nanos, 74.13_f64);
+            assert_eq!(res.part_1.unwrap().time, "74.13ns");
+            assert_eq!(res.part_1_mem.unwrap(), "3 allocs, 1.5 KiB, 1.0 KiB peak");
+            assert_eq!(res.part_2_mem.unwrap(), "0 allocs, 0 B, 0 B peak");
+        }
     }
 }
@@ -2,7 +2,16 @@ use std::process::{Command, Stdio};
 
 use crate::Day;
 
-pub fn handle(day: Day, release: bool, time: bool, submit_part: Option<u8>) {
+/// Runs the solution of `day`. `options` are passed on to the solution binary unchanged.
+pub fn handle(
+    day: Day,
+    release: bool,
+    time: bool,
+    mem: bool,
+    profile: bool,
+    submit_part: Option<u8>,
+    options: &[String],
+) {
     let mut cmd_args = vec!["run".to_string(), "--bin".to_string(), day.to_string()];
 
     if release {
@@ -20,6 +29,16 @@ pub fn handle(day: Day, release: bool, time: bool, submit_part: Option<u8>) {
         cmd_args.push("--time".to_string());
     }
 
+    if mem {
+        cmd_args.push("--mem".to_string());
+    }
+
+    if profile {
+        cmd_args.push("--profile".to_string());
+    }
+
+    cmd_args.extend_from_slice(options);
+
     let mut cmd = Command::new("cargo")
         .args(&cmd_args)
         .stdout(Stdio::inherit())
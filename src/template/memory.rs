@@ -0,0 +1,136 @@
+/// Counting global allocator backing the `--mem` mode of the runner.
+///
+/// Every solution binary installs [`CountingAllocator`] through the `solution!` macro.
+/// Counting is disabled by default, so a regular run only pays for one relaxed atomic load per allocation.
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::sync::atomic::{AtomicBool, AtomicIsize, AtomicUsize, Ordering};
+
+static ENABLED: AtomicBool = AtomicBool::new(false);
+static ALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+static BYTES_ALLOCATED: AtomicUsize = AtomicUsize::new(0);
+static CURRENT_HEAP: AtomicIsize = AtomicIsize::new(0);
+static PEAK_HEAP: AtomicIsize = AtomicIsize::new(0);
+
+/// Allocation statistics collected while running a solution part.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct MemoryStats {
+    /// Number of allocations (including reallocations).
+    pub allocations: usize,
+    /// Sum of all requested allocation sizes in bytes.
+    pub bytes_allocated: usize,
+    /// Highest amount of live heap memory in bytes, relative to the start of the measurement.
+    pub peak_heap: usize,
+}
+
+/// A [`GlobalAlloc`] that forwards to the [`System`] allocator and counts allocations while enabled.
+pub struct CountingAllocator;
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let ptr = System.alloc(layout);
+        if !ptr.is_null() && ENABLED.load(Ordering::Relaxed) {
+            record_alloc(layout.size());
+        }
+        ptr
+    }
+
+    unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+        let ptr = System.alloc_zeroed(layout);
+        if !ptr.is_null() && ENABLED.load(Ordering::Relaxed) {
+            record_alloc(layout.size());
+        }
+        ptr
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout);
+        if ENABLED.load(Ordering::Relaxed) {
+            record_dealloc(layout.size());
+        }
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        let new_ptr = System.realloc(ptr, layout, new_size);
+        if !new_ptr.is_null() && ENABLED.load(Ordering::Relaxed) {
+            record_dealloc(layout.size());
+            record_alloc(new_size);
+        }
+        new_ptr
+    }
+}
+
+#[allow(clippy::cast_possible_wrap)]
+fn record_alloc(size: usize) {
+    ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+    BYTES_ALLOCATED.fetch_add(size, Ordering::Relaxed);
+    let current = CURRENT_HEAP.fetch_add(size as isize, Ordering::Relaxed) + size as isize;
+    PEAK_HEAP.fetch_max(current, Ordering::Relaxed);
+}
+
+#[allow(clippy::cast_possible_wrap)]
+fn record_dealloc(size: usize) {
+    CURRENT_HEAP.fetch_sub(size as isize, Ordering::Relaxed);
+}
+
+fn reset() {
+    ALLOCATIONS.store(0, Ordering::Relaxed);
+    BYTES_ALLOCATED.store(0, Ordering::Relaxed);
+    CURRENT_HEAP.store(0, Ordering::Relaxed);
+    PEAK_HEAP.store(0, Ordering::Relaxed);
+}
+
+/// Runs `func` once with allocation counting enabled and returns its result together with the collected stats.
+/// The stats are all zero if [`CountingAllocator`] is not installed as the global allocator.
+pub fn measure<T>(func: impl FnOnce() -> T) -> (T, MemoryStats) {
+    reset();
+    ENABLED.store(true, Ordering::SeqCst);
+    let result = func();
+    ENABLED.store(false, Ordering::SeqCst);
+
+    let stats = MemoryStats {
+        allocations: ALLOCATIONS.load(Ordering::Relaxed),
+        bytes_allocated: BYTES_ALLOCATED.load(Ordering::Relaxed),
+        #[allow(clippy::cast_sign_loss)]
+        peak_heap: PEAK_HEAP.load(Ordering::Relaxed).max(0) as usize,
+    };
+
+    (result, stats)
+}
+
+/// Formats a byte count with a binary unit suffix, e.g. `1.5 KiB`.
+#[must_use]
+#[allow(clippy::cast_precision_loss)]
+pub fn format_bytes(bytes: usize) -> String {
+    const UNITS: [&str; 4] = ["KiB", "MiB", "GiB", "TiB"];
+
+    if bytes < 1024 {
+        return format!("{bytes} B");
+    }
+
+    let mut value = bytes as f64;
+    let mut unit = "B";
+
+    for next_unit in UNITS {
+        if value < 1024.0 {
+            break;
+        }
+        value /= 1024.0;
+        unit = next_unit;
+    }
+
+    format!("{value:.1} {unit}")
+}
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::format_bytes;
+
+    #[test]
+    fn formats_bytes() {
+        assert_eq!(format_bytes(0), "0 B");
+        assert_eq!(format_bytes(1023), "1023 B");
+        assert_eq!(format_bytes(1024), "1.0 KiB");
+        assert_eq!(format_bytes(1536), "1.5 KiB");
+        assert_eq!(format_bytes(3 * 1024 * 1024), "3.0 MiB");
+    }
+}
@@ -3,7 +3,11 @@ use std::{env, fs};
 
 pub mod aoc_cli;
 pub mod commands;
+pub mod memory;
+pub mod options;
+pub mod profiler;
 pub mod readme_benchmarks;
+pub mod report;
 pub mod runner;
 
 pub const ANSI_ITALIC: &str = "\x1b[3m";
@@ -20,12 +24,23 @@ pub fn read_file(folder: &str, day: Day) -> String {
 }
 
 /// Creates the constant `DAY` and sets up the input and runner for each part.
+///
+/// Optionally, a parse function can be passed as second argument, e.g. `solution!(5, parse_input)`.
+/// The parse function returns a `Result`; the input is then parsed once, timed separately,
+/// and both parts receive a reference to the parsed value. Parse errors are printed before exiting.
 #[macro_export]
 macro_rules! solution {
-    ($day:expr) => {
+    (@setup $day:expr) => {
         /// The current day.
         const DAY: advent_of_code::Day = advent_of_code::day!($day);
 
+        #[global_allocator]
+        static ALLOCATOR: advent_of_code::template::memory::CountingAllocator =
+            advent_of_code::template::memory::CountingAllocator;
+    };
+    ($day:expr) => {
+        advent_of_code::solution!(@setup $day);
+
         fn main() {
             use advent_of_code::template::runner::*;
             let input = advent_of_code::template::read_file("inputs", DAY);
@@ -33,4 +48,15 @@ macro_rules! solution {
             run_part(part_two, &input, DAY, 2);
         }
     };
+    ($day:expr, $parse:ident) => {
+        advent_of_code::solution!(@setup $day);
+
+        fn main() {
+            use advent_of_code::template::runner::*;
+            let input = advent_of_code::template::read_file("inputs", DAY);
+            let parsed = run_parse($parse, input.as_str(), DAY);
+            run_part(part_one, &parsed, DAY, 1);
+            run_part(part_two, &parsed, DAY, 2);
+        }
+    };
 }
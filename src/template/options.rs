@@ -0,0 +1,76 @@
+/// Options that `cargo solve` passes through to a solution binary, e.g. `cargo solve 5 --strategy reverse`.
+use std::env;
+use std::fmt::Display;
+use std::process;
+use std::str::FromStr;
+
+/// Whether the solution was started with the flag `name`, e.g. `--verbose`.
+pub fn flag(name: &str) -> bool {
+    env::args().any(|x| x == name)
+}
+
+/// The value of the option `name`, given as `--name value` or `--name=value`.
+/// Exits with an error message if the value is missing or cannot be parsed.
+pub fn value<T>(name: &str) -> Option<T>
+where
+    T: FromStr,
+    T::Err: Display,
+{
+    let raw = find_value(env::args(), name)?;
+    let Some(raw) = raw else {
+        eprintln!("Missing value for option {name}.");
+        process::exit(1);
+    };
+
+    match raw.parse() {
+        Ok(value) => Some(value),
+        Err(err) => {
+            eprintln!("Invalid value {raw:?} for option {name}: {err}");
+            process::exit(1);
+        }
+    }
+}
+
+/// Finds the value of option `name` in `args`. Returns `Some(None)` if the option is given without a value.
+fn find_value(args: impl IntoIterator<Item = String>, name: &str) -> Option<Option<String>> {
+    let mut args = args.into_iter();
+    while let Some(arg) = args.next() {
+        if arg == name {
+            return Some(args.next().filter(|value| !value.starts_with("--")));
+        }
+        if let Some(value) = arg.strip_prefix(name).and_then(|x| x.strip_prefix('=')) {
+            return Some(Some(value.to_string()));
+        }
+    }
+    None
+}
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::find_value;
+
+    fn args(args: &[&str]) -> Vec<String> {
+        args.iter().map(|arg| arg.to_string()).collect()
+    }
+
+    #[test]
+    fn finds_values() {
+        let found = find_value(
+            args(&["05", "--time", "--strategy", "reverse"]),
+            "--strategy",
+        );
+        assert_eq!(found, Some(Some("reverse".to_string())));
+
+        let found = find_value(args(&["05", "--strategy=reverse"]), "--strategy");
+        assert_eq!(found, Some(Some("reverse".to_string())));
+
+        let found = find_value(args(&["05", "--strategy", "--time"]), "--strategy");
+        assert_eq!(found, Some(None));
+
+        assert_eq!(
+            find_value(args(&["05", "--strategyx=1"]), "--strategy"),
+            None
+        );
+        assert_eq!(find_value(args(&["05"]), "--strategy"), None);
+    }
+}
@@ -0,0 +1,346 @@
+/// Instrumentation-based profiler backing the `--profile` mode of the runner.
+///
+/// Solutions mark interesting regions with [`profile_scope!`](crate::profile_scope).
+/// While a profile is recorded, every scope is added to a call tree together with its wall time.
+/// The tree can then be written as folded stacks (compatible with `flamegraph.pl` and `inferno`) or rendered as a flamegraph SVG.
+/// Outside of `--profile` mode, a scope costs a single thread-local lookup.
+use std::cell::RefCell;
+use std::fmt::Write;
+use std::time::{Duration, Instant};
+
+thread_local! {
+    static CALL_TREE: RefCell<Option<CallTree>> = const { RefCell::new(None) };
+}
+
+const SVG_WIDTH: f64 = 1200.0;
+const SVG_FRAME_HEIGHT: usize = 16;
+const SVG_PADDING: usize = 32;
+
+struct Frame {
+    name: &'static str,
+    parent: usize,
+    children: Vec<usize>,
+    total: Duration,
+    calls: u64,
+}
+
+/// The recorded call tree. The frame at index 0 is a synthetic root that holds all top-level scopes.
+struct CallTree {
+    frames: Vec<Frame>,
+    current: usize,
+}
+
+impl CallTree {
+    fn new() -> Self {
+        Self {
+            frames: vec![Frame {
+                name: "all",
+                parent: 0,
+                children: vec![],
+                total: Duration::ZERO,
+                calls: 0,
+            }],
+            current: 0,
+        }
+    }
+
+    fn enter(&mut self, name: &'static str) {
+        let existing = self.frames[self.current]
+            .children
+            .iter()
+            .copied()
+            .find(|&idx| self.frames[idx].name == name);
+
+        self.current = existing.unwrap_or_else(|| {
+            let idx = self.frames.len();
+            self.frames.push(Frame {
+                name,
+                parent: self.current,
+                children: vec![],
+                total: Duration::ZERO,
+                calls: 0,
+            });
+            self.frames[self.current].children.push(idx);
+            idx
+        });
+    }
+
+    fn exit(&mut self, elapsed: Duration) {
+        let frame = &mut self.frames[self.current];
+        frame.total += elapsed;
+        frame.calls += 1;
+        self.current = frame.parent;
+    }
+
+    fn self_time(&self, idx: usize) -> Duration {
+        let children_total: Duration = self.frames[idx]
+            .children
+            .iter()
+            .map(|&child| self.frames[child].total)
+            .sum();
+        self.frames[idx].total.saturating_sub(children_total)
+    }
+
+    fn path(&self, mut idx: usize) -> Vec<&'static str> {
+        let mut path = vec![];
+        while idx != 0 {
+            path.push(self.frames[idx].name);
+            idx = self.frames[idx].parent;
+        }
+        path.reverse();
+        path
+    }
+}
+
+/// Guard returned by [`scope`]. Records the elapsed time of the scope when dropped.
+#[must_use = "the scope ends when the guard is dropped"]
+pub struct ScopeGuard {
+    start: Option<Instant>,
+}
+
+impl Drop for ScopeGuard {
+    fn drop(&mut self) {
+        if let Some(start) = self.start {
+            let elapsed = start.elapsed();
+            CALL_TREE.with(|tree| {
+                if let Some(tree) = tree.borrow_mut().as_mut() {
+                    tree.exit(elapsed);
+                }
+            });
+        }
+    }
+}
+
+/// Opens a named profiling scope that lasts until the returned guard is dropped.
+/// Does nothing unless a profile is being recorded.
+pub fn scope(name: &'static str) -> ScopeGuard {
+    let is_recording = CALL_TREE.with(|tree| match tree.borrow_mut().as_mut() {
+        Some(tree) => {
+            tree.enter(name);
+            true
+        }
+        None => false,
+    });
+
+    ScopeGuard {
+        start: is_recording.then(Instant::now),
+    }
+}
+
+/// Marks the rest of the enclosing block as a profiling scope.
+///
+/// ```
+/// fn parse_mapping(input: &str) -> Vec<u32> {
+///     advent_of_code::profile_scope!("parse_mapping");
+///     input.lines().map(|line| line.parse().unwrap()).collect()
+/// }
+/// ```
+#[macro_export]
+macro_rules! profile_scope {
+    ($name:expr) => {
+        let _profile_scope_guard = advent_of_code::template::profiler::scope($name);
+    };
+}
+
+/// A recorded profile.
+pub struct Profile {
+    tree: CallTree,
+    pub iterations: u64,
+}
+
+/// Calls `func` repeatedly inside a scope named `root` until `budget` is used up (but at least 10 times)
+/// and returns the recorded profile.
+pub fn record<T>(root: &'static str, mut func: impl FnMut() -> T, budget: Duration) -> Profile {
+    CALL_TREE.with(|tree| *tree.borrow_mut() = Some(CallTree::new()));
+
+    let timer = Instant::now();
+    let mut iterations = 0;
+
+    while iterations < 10 || timer.elapsed() < budget {
+        let _guard = scope(root);
+        func();
+        iterations += 1;
+    }
+
+    let mut tree = CALL_TREE
+        .with(|tree| tree.borrow_mut().take())
+        .expect("call tree should be present while recording");
+    tree.frames[0].total = tree.frames[0]
+        .children
+        .iter()
+        .map(|&child| tree.frames[child].total)
+        .sum();
+
+    Profile { tree, iterations }
+}
+
+impl Profile {
+    /// Formats the profile as folded stacks, one `frame;frame;frame <self time in ns>` line per call path.
+    #[must_use]
+    pub fn to_folded(&self) -> String {
+        let mut folded = String::new();
+
+        for idx in 1..self.tree.frames.len() {
+            let self_nanos = self.tree.self_time(idx).as_nanos();
+            if self_nanos > 0 {
+                let _ = writeln!(folded, "{} {self_nanos}", self.tree.path(idx).join(";"));
+            }
+        }
+
+        folded
+    }
+
+    /// Renders the profile as a flamegraph SVG. Frame widths are proportional to their total time.
+    #[must_use]
+    pub fn to_svg(&self, title: &str) -> String {
+        let depth = self.depth(0);
+        let height = depth * SVG_FRAME_HEIGHT + 2 * SVG_PADDING;
+
+        let mut svg = String::new();
+        let _ = writeln!(
+            svg,
+            r#"<svg xmlns="http://www.w3.org/2000/svg" width="{SVG_WIDTH}" height="{height}" viewBox="0 0 {SVG_WIDTH} {height}" font-family="monospace" font-size="12">"#
+        );
+        let _ = writeln!(
+            svg,
+            r##"<rect x="0" y="0" width="100%" height="100%" fill="#fdf6e3"/>"##
+        );
+        let _ = writeln!(
+            svg,
+            r#"<text x="{}" y="20" text-anchor="middle" font-size="16">{}</text>"#,
+            SVG_WIDTH / 2.0,
+            escape_xml(title)
+        );
+
+        let root_nanos = self.tree.frames[0].total.as_nanos().max(1);
+        let mut x = 0.0;
+
+        for &child in &self.tree.frames[0].children {
+            x += self.render_frame(&mut svg, child, x, 0, root_nanos, height);
+        }
+
+        svg.push_str("</svg>\n");
+        svg
+    }
+
+    fn depth(&self, idx: usize) -> usize {
+        self.tree.frames[idx]
+            .children
+            .iter()
+            .map(|&child| 1 + self.depth(child))
+            .max()
+            .unwrap_or(0)
+    }
+
+    /// Renders a frame and its children, returns the width of the frame.
+    #[allow(clippy::cast_precision_loss)]
+    fn render_frame(
+        &self,
+        svg: &mut String,
+        idx: usize,
+        x: f64,
+        level: usize,
+        root_nanos: u128,
+        height: usize,
+    ) -> f64 {
+        let frame = &self.tree.frames[idx];
+        let width = frame.total.as_nanos() as f64 / root_nanos as f64 * SVG_WIDTH;
+        let y = height - SVG_PADDING - (level + 1) * SVG_FRAME_HEIGHT;
+        let percentage = frame.total.as_nanos() as f64 / root_nanos as f64 * 100.0;
+        let name = escape_xml(frame.name);
+
+        let _ = writeln!(
+            svg,
+            r#"<g><title>{name} ({:.1?}, {} calls, {percentage:.2}%)</title><rect x="{x:.2}" y="{y}" width="{width:.2}" height="{}" fill="{}" stroke="white" stroke-width="0.5"/>"#,
+            frame.total,
+            frame.calls,
+            SVG_FRAME_HEIGHT,
+            frame_color(frame.name),
+        );
+
+        // only label frames that are wide enough to fit a few characters.
+        if width > 30.0 {
+            let max_chars = ((width - 6.0) / 7.5) as usize;
+            let label: String = frame.name.chars().take(max_chars).collect();
+            let _ = write!(
+                svg,
+                r#"<text x="{:.2}" y="{}">{}</text>"#,
+                x + 3.0,
+                y + SVG_FRAME_HEIGHT - 4,
+                escape_xml(&label)
+            );
+        }
+
+        svg.push_str("</g>\n");
+
+        let mut child_x = x;
+        for &child in &frame.children {
+            child_x += self.render_frame(svg, child, child_x, level + 1, root_nanos, height);
+        }
+
+        width
+    }
+}
+
+/// Picks a warm color from the frame name so that the same frame has the same color across profiles.
+fn frame_color(name: &str) -> String {
+    let hash = name
+        .bytes()
+        .fold(5381_u32, |hash, b| hash.wrapping_mul(33) ^ u32::from(b));
+    let red = 205 + hash % 50;
+    let green = (hash >> 8) % 180;
+    let blue = (hash >> 16) % 55;
+    format!("rgb({red},{green},{blue})")
+}
+
+fn escape_xml(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use std::time::Duration;
+
+    use super::{record, scope};
+
+    fn busy() -> u64 {
+        let _guard = scope("inner");
+        (0..1000_u64).map(std::hint::black_box).sum()
+    }
+
+    #[test]
+    fn records_nested_scopes() {
+        let profile = record(
+            "root",
+            || {
+                busy();
+                busy()
+            },
+            Duration::ZERO,
+        );
+        assert_eq!(profile.iterations, 10);
+
+        let folded = profile.to_folded();
+        assert!(folded.lines().any(|l| l.starts_with("root;inner ")));
+        assert!(folded.lines().all(|l| l.starts_with("root")));
+    }
+
+    #[test]
+    fn scopes_are_inactive_outside_of_recording() {
+        let guard = scope("noop");
+        assert!(guard.start.is_none());
+    }
+
+    #[test]
+    fn renders_svg() {
+        let profile = record("root", busy, Duration::ZERO);
+        let svg = profile.to_svg("Day <01>");
+        assert!(svg.starts_with("<svg"));
+        assert!(svg.contains("Day &lt;01&gt;"));
+        assert!(svg.contains("inner"));
+        assert!(svg.trim_end().ends_with("</svg>"));
+    }
+}
@@ -1,5 +1,6 @@
 /// Module that updates the readme me with timing information.
 /// The approach taken is similar to how `aoc-readme-stars` handles this.
+use std::fmt::Display;
 use std::{fs, io};
 
 use crate::Day;
@@ -8,24 +9,69 @@ static MARKER: &str = "<!--- benchmarking table --->";
 
 #[derive(Debug)]
 pub enum Error {
-    Parser(String),
+    /// The marker occurs more than twice. Contains the line numbers of all occurrences.
+    TooManyMarkers(Vec<usize>),
+    /// A line between the markers is not part of a benchmark table.
+    UnexpectedContent {
+        line: usize,
+        content: String,
+    },
     IO(io::Error),
 }
 
+impl std::error::Error for Error {}
+
+impl Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Error::TooManyMarkers(lines) => {
+                let lines: Vec<String> = lines.iter().map(ToString::to_string).collect();
+                write!(
+                    f,
+                    "expected at most two \"{MARKER}\" markers in README, found {} on lines {}.",
+                    lines.len(),
+                    lines.join(", ")
+                )
+            }
+            Error::UnexpectedContent { line, content } => write!(
+                f,
+                "line {line} between the benchmark markers is not part of a benchmark table: \"{content}\"."
+            ),
+            Error::IO(e) => write!(f, "could not read or write README: {e}"),
+        }
+    }
+}
+
 impl From<std::io::Error> for Error {
     fn from(e: std::io::Error) -> Self {
         Error::IO(e)
     }
 }
 
+/// Benchmark result of a single phase (the parse step or a part) of a solution.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Measurement {
+    /// The average execution time as printed by the runner, e.g. `74.13ns`.
+    pub time: String,
+    pub nanos: f64,
+    pub samples: u128,
+}
+
 #[derive(Clone)]
 pub struct Timings {
     pub day: Day,
-    pub part_1: Option<String>,
-    pub part_2: Option<String>,
+    pub parse: Option<Measurement>,
+    pub part_1: Option<Measurement>,
+    pub part_2: Option<Measurement>,
+    pub parse_mem: Option<String>,
+    pub part_1_mem: Option<String>,
+    pub part_2_mem: Option<String>,
     pub total_nanos: f64,
 }
 
+/// A table column: the header and an accessor for the cell value.
+type Column = (&'static str, fn(&Timings) -> Option<&str>);
+
 pub struct TablePosition {
     pos_start: usize,
     pos_end: usize,
@@ -36,47 +82,199 @@ pub fn get_path_for_bin(day: Day) -> String {
     format!("./src/bin/{day}.rs")
 }
 
-fn locate_table(readme: &str) -> Result<TablePosition, Error> {
-    let matches: Vec<_> = readme.match_indices(MARKER).collect();
+/// Parses a duration as printed by the runner (e.g. `74.13ns`, `1.2ms`) into nanoseconds.
+#[must_use]
+pub fn parse_duration(s: &str) -> Option<f64> {
+    // for possible time formats, see: https://github.com/rust-lang/rust/blob/1.64.0/library/core/src/time.rs#L1176-L1200
+    let parse_to_float = |postfix: &str| s.split(postfix).next()?.parse::<f64>().ok();
+
+    match s {
+        s if s.contains("ns") => parse_to_float("ns"),
+        s if s.contains("µs") => parse_to_float("µs").map(|x| x * 1000_f64),
+        s if s.contains("ms") => parse_to_float("ms").map(|x| x * 1_000_000_f64),
+        _ => parse_to_float("s").map(|x| x * 1_000_000_000_f64),
+    }
+}
+
+fn line_number(s: &str, pos: usize) -> usize {
+    s[..pos].matches('\n').count() + 1
+}
+
+/// Locates the benchmark table. A single marker acts as a placeholder for an empty table.
+/// Only lines that consist of the marker count, so the readme can mention it in prose.
+/// Returns `None` if the readme contains no marker at all.
+fn locate_table(readme: &str) -> Result<Option<TablePosition>, Error> {
+    let mut matches: Vec<usize> = vec![];
+    let mut pos = 0;
+    for line in readme.split_inclusive('\n') {
+        if line.trim_end_matches(['\r', '\n']) == MARKER {
+            matches.push(pos);
+        }
+        pos += line.len();
+    }
 
     if matches.len() > 2 {
-        return Err(Error::Parser(
-            "{}: too many occurences of marker in README.".into(),
+        return Err(Error::TooManyMarkers(
+            matches
+                .iter()
+                .map(|&pos| line_number(readme, pos))
+                .collect(),
         ));
     }
 
-    let pos_start = matches
-        .first()
-        .map(|m| m.0)
-        .ok_or_else(|| Error::Parser("Could not find table start position.".into()))?;
+    let (Some(first), Some(last)) = (matches.first(), matches.last()) else {
+        return Ok(None);
+    };
+
+    Ok(Some(TablePosition {
+        pos_start: *first,
+        pos_end: last + MARKER.len(),
+    }))
+}
+
+/// Reads the rows of an existing benchmark table so that days which were not run can be preserved.
+fn parse_table(readme: &str, position: &TablePosition) -> Result<Vec<Timings>, Error> {
+    let first_line = line_number(readme, position.pos_start);
+    let mut columns: Vec<String> = vec![];
+    let mut timings = vec![];
+
+    for (idx, line) in readme[position.pos_start..position.pos_end]
+        .lines()
+        .enumerate()
+    {
+        let line = line.replace(MARKER, "");
+        let line = line.trim();
+
+        if line.is_empty()
+            || line.starts_with("| :")
+            || line.starts_with("**Total")
+            || (line.starts_with('#') && line.ends_with("Benchmarks"))
+        {
+            continue;
+        }
+
+        let unexpected = || Error::UnexpectedContent {
+            line: first_line + idx,
+            content: line.to_string(),
+        };
+
+        let cells: Vec<&str> = line
+            .strip_prefix('|')
+            .and_then(|l| l.strip_suffix('|'))
+            .ok_or_else(unexpected)?
+            .split('|')
+            .map(str::trim)
+            .collect();
+
+        if cells[0] == "Day" {
+            columns = cells[1..].iter().map(ToString::to_string).collect();
+            continue;
+        }
+
+        let day = cells[0]
+            .strip_prefix("[Day ")
+            .and_then(|c| c.split(']').next())
+            .and_then(|c| c.parse::<u8>().ok())
+            .and_then(Day::new)
+            .ok_or_else(unexpected)?;
+
+        let mut timing = Timings {
+            day,
+            parse: None,
+            part_1: None,
+            part_2: None,
+            parse_mem: None,
+            part_1_mem: None,
+            part_2_mem: None,
+            total_nanos: 0_f64,
+        };
 
-    let pos_end = matches
-        .last()
-        .map(|m| m.0 + m.1.len())
-        .ok_or_else(|| Error::Parser("Could not find table end position.".into()))?;
+        for (column, cell) in columns.iter().zip(cells.iter().skip(1)) {
+            let value = cell.trim_matches('`');
+            if value == "-" {
+                continue;
+            }
 
-    Ok(TablePosition { pos_start, pos_end })
+            let measurement = || {
+                let nanos = parse_duration(value)?;
+                Some(Measurement {
+                    time: value.to_string(),
+                    nanos,
+                    samples: 0,
+                })
+            };
+
+            match column.as_str() {
+                "Parse" => timing.parse = measurement(),
+                "Part 1" => timing.part_1 = measurement(),
+                "Part 2" => timing.part_2 = measurement(),
+                "Memory parse" => timing.parse_mem = Some(value.to_string()),
+                "Memory 1" => timing.part_1_mem = Some(value.to_string()),
+                "Memory 2" => timing.part_2_mem = Some(value.to_string()),
+                _ => {}
+            }
+        }
+
+        timing.total_nanos = [&timing.parse, &timing.part_1, &timing.part_2]
+            .iter()
+            .filter_map(|m| m.as_ref().map(|m| m.nanos))
+            .sum();
+
+        timings.push(timing);
+    }
+
+    Ok(timings)
 }
 
 fn construct_table(prefix: &str, timings: Vec<Timings>, total_millis: f64) -> String {
     let header = format!("{prefix} Benchmarks");
 
+    // the parse column is only shown if a solution uses a separate parse step,
+    // memory columns are only shown if the solutions were run with `--mem`.
+    let has_parse = timings.iter().any(|t| t.parse.is_some());
+    let has_mem = timings
+        .iter()
+        .any(|t| t.parse_mem.is_some() || t.part_1_mem.is_some() || t.part_2_mem.is_some());
+
+    let mut columns: Vec<Column> = vec![];
+
+    if has_parse {
+        columns.push(("Parse", |t| t.parse.as_ref().map(|m| m.time.as_str())));
+    }
+
+    columns.push(("Part 1", |t| t.part_1.as_ref().map(|m| m.time.as_str())));
+    columns.push(("Part 2", |t| t.part_2.as_ref().map(|m| m.time.as_str())));
+
+    if has_mem {
+        if has_parse {
+            columns.push(("Memory parse", |t| t.parse_mem.as_deref()));
+        }
+        columns.push(("Memory 1", |t| t.part_1_mem.as_deref()));
+        columns.push(("Memory 2", |t| t.part_2_mem.as_deref()));
+    }
+
+    let header_cells: Vec<&str> = columns.iter().map(|(name, _)| *name).collect();
+
     let mut lines: Vec<String> = vec![
         MARKER.into(),
         header,
         String::new(),
-        "| Day | Part 1 | Part 2 |".into(),
-        "| :---: | :---: | :---:  |".into(),
+        format!("| Day | {} |", header_cells.join(" | ")),
+        format!("| :---: | {}  |", vec![":---:"; columns.len()].join(" | ")),
     ];
 
     for timing in timings {
         let path = get_path_for_bin(timing.day);
+        let cells: Vec<String> = columns
+            .iter()
+            .map(|(_, get)| format!("`{}`", get(&timing).unwrap_or("-")))
+            .collect();
+
         lines.push(format!(
-            "| [Day {}]({}) | `{}` | `{}` |",
+            "| [Day {}]({}) | {} |",
             timing.day.into_inner(),
             path,
-            timing.part_1.unwrap_or_else(|| "-".into()),
-            timing.part_2.unwrap_or_else(|| "-".into())
+            cells.join(" | ")
         ));
     }
 
@@ -87,73 +285,247 @@ fn construct_table(prefix: &str, timings: Vec<Timings>, total_millis: f64) -> St
     lines.join("\n")
 }
 
+/// Replaces the benchmark table in `s`, creating it at the end if the readme has no markers.
+/// Rows of days that are not part of `timings` are kept, their times are added to the total.
 fn update_content(s: &mut String, timings: Vec<Timings>, total_millis: f64) -> Result<(), Error> {
-    let positions = locate_table(s)?;
-    let table = construct_table("##", timings, total_millis);
-    s.replace_range(positions.pos_start..positions.pos_end, &table);
+    let Some(position) = locate_table(s)? else {
+        let table = construct_table("##", timings, total_millis);
+        let separator = if s.is_empty() || s.ends_with("\n\n") {
+            ""
+        } else if s.ends_with('\n') {
+            "\n"
+        } else {
+            "\n\n"
+        };
+        s.push_str(separator);
+        s.push_str(&table);
+        s.push('\n');
+        return Ok(());
+    };
+
+    let mut preserved: Vec<Timings> = parse_table(s, &position)?
+        .into_iter()
+        .filter(|existing| !timings.iter().any(|t| t.day == existing.day))
+        .collect();
+
+    let total_millis =
+        total_millis + preserved.iter().map(|t| t.total_nanos).sum::<f64>() / 1_000_000_f64;
+
+    preserved.extend(timings);
+    preserved.sort_by_key(|t| t.day);
+
+    let table = construct_table("##", preserved, total_millis);
+    s.replace_range(position.pos_start..position.pos_end, &table);
     Ok(())
 }
 
+fn read_readme(path: &str) -> Result<String, Error> {
+    Ok(String::from_utf8_lossy(&fs::read(path)?).to_string())
+}
+
 pub fn update(timings: Vec<Timings>, total_millis: f64) -> Result<(), Error> {
     let path = "README.md";
-    let mut readme = String::from_utf8_lossy(&fs::read(path)?).to_string();
+    let mut readme = read_readme(path)?;
     update_content(&mut readme, timings, total_millis)?;
     fs::write(path, &readme)?;
     Ok(())
 }
 
+/// Computes the changes `update` would make to the readme, formatted as a line diff.
+pub fn preview(timings: Vec<Timings>, total_millis: f64) -> Result<String, Error> {
+    let readme = read_readme("README.md")?;
+    let mut updated = readme.clone();
+    update_content(&mut updated, timings, total_millis)?;
+    Ok(diff_lines(&readme, &updated))
+}
+
+/// A minimal line-based diff (longest common subsequence) that prints changed lines with two lines of context.
+fn diff_lines(old: &str, new: &str) -> String {
+    const CONTEXT: usize = 2;
+
+    let old: Vec<&str> = old.lines().collect();
+    let new: Vec<&str> = new.lines().collect();
+
+    // lcs[i][j] is the length of the longest common subsequence of old[i..] and new[j..].
+    let mut lcs = vec![vec![0_usize; new.len() + 1]; old.len() + 1];
+    for i in (0..old.len()).rev() {
+        for j in (0..new.len()).rev() {
+            lcs[i][j] = if old[i] == new[j] {
+                lcs[i + 1][j + 1] + 1
+            } else {
+                lcs[i + 1][j].max(lcs[i][j + 1])
+            };
+        }
+    }
+
+    // (prefix, old line number, text)
+    let mut ops: Vec<(char, usize, &str)> = vec![];
+    let (mut i, mut j) = (0, 0);
+    while i < old.len() || j < new.len() {
+        if i < old.len() && j < new.len() && old[i] == new[j] {
+            ops.push((' ', i + 1, old[i]));
+            i += 1;
+            j += 1;
+        } else if i < old.len() && (j == new.len() || lcs[i + 1][j] >= lcs[i][j + 1]) {
+            ops.push(('-', i + 1, old[i]));
+            i += 1;
+        } else {
+            ops.push(('+', i + 1, new[j]));
+            j += 1;
+        }
+    }
+
+    let changed: Vec<usize> = (0..ops.len()).filter(|&k| ops[k].0 != ' ').collect();
+    let mut output = String::new();
+    let mut last_printed: Option<usize> = None;
+
+    for (k, op) in ops.iter().enumerate() {
+        let is_visible = changed
+            .iter()
+            .any(|&c| k + CONTEXT >= c && k <= c + CONTEXT);
+        if !is_visible {
+            continue;
+        }
+        if last_printed.is_none_or(|last| last + 1 != k) {
+            output.push_str(&format!("@@ line {} @@\n", op.1));
+        }
+        output.push_str(&format!("{} {}\n", op.0, op.2));
+        last_printed = Some(k);
+    }
+
+    output
+}
+
 #[cfg(feature = "test_lib")]
 mod tests {
-    use super::{update_content, Timings, MARKER};
+    use super::{diff_lines, update_content, Error, Measurement, Timings, MARKER};
     use crate::day;
 
+    fn measurement(time: &str) -> Option<Measurement> {
+        Some(Measurement {
+            time: time.into(),
+            nanos: 0.0,
+            samples: 10,
+        })
+    }
+
     fn get_mock_timings() -> Vec<Timings> {
         vec![
             Timings {
                 day: day!(1),
-                part_1: Some("10ms".into()),
-                part_2: Some("20ms".into()),
+                parse: None,
+                part_1: measurement("10ms"),
+                part_2: measurement("20ms"),
+                parse_mem: None,
+                part_1_mem: None,
+                part_2_mem: None,
                 total_nanos: 3e+10,
             },
             Timings {
                 day: day!(2),
-                part_1: Some("30ms".into()),
-                part_2: Some("40ms".into()),
+                parse: None,
+                part_1: measurement("30ms"),
+                part_2: measurement("40ms"),
+                parse_mem: None,
+                part_1_mem: None,
+                part_2_mem: None,
                 total_nanos: 7e+10,
             },
             Timings {
                 day: day!(4),
-                part_1: Some("40ms".into()),
-                part_2: Some("50ms".into()),
+                parse: None,
+                part_1: measurement("40ms"),
+                part_2: measurement("50ms"),
+                parse_mem: None,
+                part_1_mem: None,
+                part_2_mem: None,
                 total_nanos: 9e+10,
             },
         ]
     }
 
     #[test]
-    #[should_panic]
-    fn errors_if_marker_not_present() {
+    fn creates_table_if_marker_not_present() {
         let mut s = "# readme".to_string();
         update_content(&mut s, get_mock_timings(), 190.0).unwrap();
+        assert!(s.starts_with(&format!("# readme\n\n{MARKER}\n## Benchmarks")));
+        assert!(s.ends_with(&format!("**Total: 190.00ms**\n{MARKER}\n")));
     }
 
     #[test]
     #[should_panic]
     fn errors_if_too_many_markers_present() {
-        let mut s = format!("{} {} {}", MARKER, MARKER, MARKER);
+        let mut s = format!("{MARKER}\n{MARKER}\n{MARKER}");
+        update_content(&mut s, get_mock_timings(), 190.0).unwrap();
+    }
+
+    #[test]
+    fn reports_lines_of_duplicate_markers() {
+        let mut s = format!("foo\n{MARKER}\n{MARKER}\nbar\n{MARKER}");
+        let err = update_content(&mut s, get_mock_timings(), 190.0).unwrap_err();
+        assert!(matches!(err, Error::TooManyMarkers(ref lines) if lines == &[2, 3, 5]));
+        assert!(err.to_string().contains("found 3 on lines 2, 3, 5"));
+    }
+
+    #[test]
+    fn errors_on_unexpected_content_between_markers() {
+        let mut s = format!("foo\n{MARKER}\nsome notes\n{MARKER}");
+        let err = update_content(&mut s, get_mock_timings(), 190.0).unwrap_err();
+        assert!(matches!(err, Error::UnexpectedContent { line: 3, .. }));
+    }
+
+    #[test]
+    fn preserves_rows_of_days_not_run() {
+        let mut s = format!("foo\n{}\nbaz", MARKER);
+        update_content(&mut s, get_mock_timings(), 190.0).unwrap();
+
+        let mut timings = get_mock_timings();
+        timings.truncate(1);
+        timings[0].part_1 = measurement("1ms");
+        update_content(&mut s, timings, 3.0).unwrap();
+
+        assert!(s.contains("| [Day 1](./src/bin/01.rs) | `1ms` | `20ms` |"));
+        assert!(s.contains("| [Day 2](./src/bin/02.rs) | `30ms` | `40ms` |"));
+        assert!(s.contains("| [Day 4](./src/bin/04.rs) | `40ms` | `50ms` |"));
+        // 3ms for day 1 and 160ms for the preserved days.
+        assert!(s.contains("**Total: 163.00ms**"));
+    }
+
+    #[test]
+    fn diffs_changed_lines() {
+        let diff = diff_lines("a\nb\nc\nd\ne\nf\ng", "a\nb\nc\nD\ne\nf\ng");
+        assert_eq!(diff, "@@ line 2 @@\n  b\n  c\n- d\n+ D\n  e\n  f\n");
+        assert_eq!(diff_lines("a\nb", "a\nb"), "");
+    }
+
+    #[test]
+    fn ignores_markers_within_a_line() {
+        let mut s = format!("foo\nthe `{MARKER}` marker\n{MARKER}\nbaz");
+        update_content(&mut s, get_mock_timings(), 190.0).unwrap();
+        assert!(s.starts_with(&format!(
+            "foo\nthe `{MARKER}` marker\n{MARKER}\n## Benchmarks"
+        )));
+    }
+
+    #[test]
+    fn updates_the_readme_of_the_repository() {
+        let mut s = include_str!("../../README.md").to_string();
         update_content(&mut s, get_mock_timings(), 190.0).unwrap();
+        assert_eq!(s.matches("## Benchmarks").count(), 1);
+        assert!(s.contains("| [Day 4](./src/bin/04.rs) | `40ms` | `50ms` |"));
+        assert!(s.contains("## Template setup"));
     }
 
     #[test]
     fn updates_empty_benchmarks() {
-        let mut s = format!("foo\nbar\n{}{}\nbaz", MARKER, MARKER);
+        let mut s = format!("foo\nbar\n{}\n{}\nbaz", MARKER, MARKER);
         update_content(&mut s, get_mock_timings(), 190.0).unwrap();
         assert_eq!(s.contains("## Benchmarks"), true);
     }
 
     #[test]
     fn updates_existing_benchmarks() {
-        let mut s = format!("foo\nbar\n{}{}\nbaz", MARKER, MARKER);
+        let mut s = format!("foo\nbar\n{}\n{}\nbaz", MARKER, MARKER);
         update_content(&mut s, get_mock_timings(), 190.0).unwrap();
         update_content(&mut s, get_mock_timings(), 190.0).unwrap();
         assert_eq!(s.matches(MARKER).collect::<Vec<&str>>().len(), 2);
@@ -183,4 +555,32 @@ mod tests {
         .join("\n");
         assert_eq!(s, expected);
     }
+
+    #[test]
+    fn format_benchmarks_with_memory() {
+        let mut timings = get_mock_timings();
+        timings[0].part_1_mem = Some("2 allocs, 64 B, 64 B peak".into());
+
+        let mut s = format!("{}\n{}", MARKER, MARKER);
+        update_content(&mut s, timings, 190.0).unwrap();
+
+        assert!(s.contains("| Day | Part 1 | Part 2 | Memory 1 | Memory 2 |"));
+        assert!(s.contains(
+            "| [Day 1](./src/bin/01.rs) | `10ms` | `20ms` | `2 allocs, 64 B, 64 B peak` | `-` |"
+        ));
+        assert!(s.contains("| [Day 2](./src/bin/02.rs) | `30ms` | `40ms` | `-` | `-` |"));
+    }
+
+    #[test]
+    fn format_benchmarks_with_parse() {
+        let mut timings = get_mock_timings();
+        timings[1].parse = measurement("5ms");
+
+        let mut s = format!("{}\n{}", MARKER, MARKER);
+        update_content(&mut s, timings, 190.0).unwrap();
+
+        assert!(s.contains("| Day | Parse | Part 1 | Part 2 |"));
+        assert!(s.contains("| [Day 1](./src/bin/01.rs) | `-` | `10ms` | `20ms` |"));
+        assert!(s.contains("| [Day 2](./src/bin/02.rs) | `5ms` | `30ms` | `40ms` |"));
+    }
 }
@@ -0,0 +1,414 @@
+/// Module that writes standalone benchmark reports.
+/// Unlike `readme_benchmarks`, the reports include samples, machine information and the current git commit
+/// so they can be shared without touching the readme.
+use std::fmt::{Display, Write};
+use std::process::Command;
+use std::str::FromStr;
+use std::{env, fs, io, thread};
+
+use crate::template::readme_benchmarks::{Measurement, Timings};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    Markdown,
+    Csv,
+    Json,
+}
+
+impl Format {
+    /// Guesses the report format from the extension of `path`, defaults to [`Format::Markdown`].
+    #[must_use]
+    pub fn from_path(path: &str) -> Self {
+        match path.rsplit_once('.').map(|(_, ext)| ext.to_lowercase()) {
+            Some(ext) if ext == "csv" => Format::Csv,
+            Some(ext) if ext == "json" => Format::Json,
+            _ => Format::Markdown,
+        }
+    }
+}
+
+impl FromStr for Format {
+    type Err = FormatFromStrError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "md" | "markdown" => Ok(Format::Markdown),
+            "csv" => Ok(Format::Csv),
+            "json" => Ok(Format::Json),
+            _ => Err(FormatFromStrError),
+        }
+    }
+}
+
+/// An error which can be returned when parsing a report [`Format`].
+#[derive(Debug)]
+pub struct FormatFromStrError;
+
+impl std::error::Error for FormatFromStrError {}
+
+impl Display for FormatFromStrError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("expecting a report format of md, csv or json")
+    }
+}
+
+/// Where and in which format `cargo all --report` writes the report.
+pub struct Target {
+    pub path: String,
+    pub format: Format,
+}
+
+/// Information about the environment the benchmarks were run in.
+pub struct Environment {
+    pub commit: Option<String>,
+    pub is_dirty: bool,
+    pub os: &'static str,
+    pub arch: &'static str,
+    pub cpus: usize,
+    pub cpu_model: Option<String>,
+    pub rustc: Option<String>,
+    pub is_release: bool,
+}
+
+impl Environment {
+    #[must_use]
+    pub fn detect(is_release: bool) -> Self {
+        let is_dirty = command_output("git", &["status", "--porcelain"])
+            .is_some_and(|status| !status.is_empty());
+
+        Environment {
+            commit: command_output("git", &["rev-parse", "--short", "HEAD"]),
+            is_dirty,
+            os: env::consts::OS,
+            arch: env::consts::ARCH,
+            cpus: thread::available_parallelism().map_or(1, usize::from),
+            cpu_model: cpu_model(),
+            rustc: command_output("rustc", &["--version"]),
+            is_release,
+        }
+    }
+
+    fn commit_str(&self) -> String {
+        match (&self.commit, self.is_dirty) {
+            (Some(commit), true) => format!("{commit}-dirty"),
+            (Some(commit), false) => commit.clone(),
+            (None, _) => "unknown".into(),
+        }
+    }
+
+    fn machine_str(&self) -> String {
+        format!(
+            "{} {}, {} CPUs, {}",
+            self.os,
+            self.arch,
+            self.cpus,
+            self.cpu_model.as_deref().unwrap_or("unknown CPU")
+        )
+    }
+
+    fn profile_str(&self) -> &'static str {
+        if self.is_release {
+            "release"
+        } else {
+            "debug"
+        }
+    }
+}
+
+fn command_output(cmd: &str, args: &[&str]) -> Option<String> {
+    let output = Command::new(cmd).args(args).output().ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    Some(String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+fn cpu_model() -> Option<String> {
+    if cfg!(target_os = "macos") {
+        return command_output("sysctl", &["-n", "machdep.cpu.brand_string"]);
+    }
+
+    fs::read_to_string("/proc/cpuinfo")
+        .ok()?
+        .lines()
+        .find(|line| line.starts_with("model name"))
+        .and_then(|line| line.split_once(':'))
+        .map(|(_, model)| model.trim().to_string())
+}
+
+pub fn write(
+    target: &Target,
+    timings: &[Timings],
+    total_millis: f64,
+    environment: &Environment,
+) -> Result<(), io::Error> {
+    let content = match target.format {
+        Format::Markdown => to_markdown(timings, total_millis, environment),
+        Format::Csv => to_csv(timings, total_millis, environment),
+        Format::Json => to_json(timings, total_millis, environment),
+    };
+    fs::write(&target.path, content)
+}
+
+fn phases(timing: &Timings) -> [(&'static str, Option<&Measurement>, Option<&str>); 3] {
+    [
+        ("parse", timing.parse.as_ref(), timing.parse_mem.as_deref()),
+        (
+            "part_1",
+            timing.part_1.as_ref(),
+            timing.part_1_mem.as_deref(),
+        ),
+        (
+            "part_2",
+            timing.part_2.as_ref(),
+            timing.part_2_mem.as_deref(),
+        ),
+    ]
+}
+
+fn to_markdown(timings: &[Timings], total_millis: f64, environment: &Environment) -> String {
+    let mut lines: Vec<String> = vec![
+        "# Benchmarks".into(),
+        String::new(),
+        format!("- **Commit:** `{}`", environment.commit_str()),
+        format!("- **Machine:** {}", environment.machine_str()),
+        format!(
+            "- **Rust:** {}",
+            environment.rustc.as_deref().unwrap_or("unknown")
+        ),
+        format!("- **Profile:** {}", environment.profile_str()),
+        format!("- **Total:** {total_millis:.2}ms"),
+        String::new(),
+        "| Day | Parse | Part 1 | Part 2 | Total |".into(),
+        "| :---: | :---: | :---: | :---: | :---: |".into(),
+    ];
+
+    for timing in timings {
+        let cells: Vec<String> = phases(timing)
+            .iter()
+            .map(|(_, measurement, mem)| {
+                let mut cell = measurement.map_or_else(
+                    || "-".into(),
+                    |m| format!("`{}` @ {} samples", m.time, m.samples),
+                );
+                if let Some(mem) = mem {
+                    let _ = write!(cell, "<br>`{mem}`");
+                }
+                cell
+            })
+            .collect();
+
+        lines.push(format!(
+            "| {} | {} | {:.2}ms |",
+            timing.day.into_inner(),
+            cells.join(" | "),
+            timing.total_nanos / 1_000_000_f64
+        ));
+    }
+
+    lines.push(String::new());
+    lines.join("\n")
+}
+
+fn to_csv(timings: &[Timings], total_millis: f64, environment: &Environment) -> String {
+    let commit = csv_field(&environment.commit_str());
+    let machine = csv_field(&environment.machine_str());
+    let profile = environment.profile_str();
+
+    let mut lines: Vec<String> =
+        vec!["day,phase,time,nanos,samples,memory,commit,machine,profile".into()];
+
+    for timing in timings {
+        for (phase, measurement, mem) in phases(timing) {
+            let Some(measurement) = measurement else {
+                continue;
+            };
+            lines.push(format!(
+                "{},{phase},{},{:.0},{},{},{commit},{machine},{profile}",
+                timing.day.into_inner(),
+                csv_field(&measurement.time),
+                measurement.nanos,
+                measurement.samples,
+                csv_field(mem.unwrap_or_default()),
+            ));
+        }
+    }
+
+    lines.push(format!(
+        "total,,{total_millis:.2}ms,{:.0},,,{commit},{machine},{profile}",
+        total_millis * 1_000_000_f64
+    ));
+    lines.push(String::new());
+    lines.join("\n")
+}
+
+fn csv_field(s: &str) -> String {
+    if s.contains([',', '"', '\n']) {
+        format!("\"{}\"", s.replace('"', "\"\""))
+    } else {
+        s.to_string()
+    }
+}
+
+fn to_json(timings: &[Timings], total_millis: f64, environment: &Environment) -> String {
+    let days: Vec<String> = timings
+        .iter()
+        .map(|timing| {
+            let phases: Vec<String> = phases(timing)
+                .iter()
+                .map(|(phase, measurement, mem)| {
+                    let value = measurement.map_or_else(
+                        || "null".into(),
+                        |m| {
+                            format!(
+                                r#"{{"time": {}, "nanos": {:.0}, "samples": {}, "memory": {}}}"#,
+                                json_string(&m.time),
+                                m.nanos,
+                                m.samples,
+                                mem.map_or_else(|| "null".into(), json_string)
+                            )
+                        },
+                    );
+                    format!(r#""{phase}": {value}"#)
+                })
+                .collect();
+
+            format!(
+                r#"    {{"day": {}, "total_nanos": {:.0}, {}}}"#,
+                timing.day.into_inner(),
+                timing.total_nanos,
+                phases.join(", ")
+            )
+        })
+        .collect();
+
+    let optional =
+        |value: &Option<String>| value.as_deref().map_or_else(|| "null".into(), json_string);
+
+    [
+        "{".to_string(),
+        format!(r#"  "commit": {},"#, optional(&environment.commit)),
+        format!(r#"  "dirty": {},"#, environment.is_dirty),
+        format!(
+            r#"  "machine": {{"os": {}, "arch": {}, "cpus": {}, "cpu_model": {}}},"#,
+            json_string(environment.os),
+            json_string(environment.arch),
+            environment.cpus,
+            optional(&environment.cpu_model)
+        ),
+        format!(r#"  "rustc": {},"#, optional(&environment.rustc)),
+        format!(r#"  "profile": "{}","#, environment.profile_str()),
+        format!(r#"  "total_nanos": {:.0},"#, total_millis * 1_000_000_f64),
+        r#"  "days": ["#.to_string(),
+        days.join(",\n"),
+        "  ]".to_string(),
+        "}".to_string(),
+        String::new(),
+    ]
+    .join("\n")
+}
+
+fn json_string(s: &str) -> String {
+    let mut escaped = String::with_capacity(s.len() + 2);
+    escaped.push('"');
+    for ch in s.chars() {
+        match ch {
+            '"' => escaped.push_str("\\\""),
+            '\\' => escaped.push_str("\\\\"),
+            '\n' => escaped.push_str("\\n"),
+            '\r' => escaped.push_str("\\r"),
+            '\t' => escaped.push_str("\\t"),
+            c if c.is_control() => {
+                let _ = write!(escaped, "\\u{:04x}", c as u32);
+            }
+            c => escaped.push(c),
+        }
+    }
+    escaped.push('"');
+    escaped
+}
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::{json_string, to_csv, to_json, to_markdown, Environment, Format};
+    use crate::day;
+    use crate::template::readme_benchmarks::{Measurement, Timings};
+
+    fn get_mock_environment() -> Environment {
+        Environment {
+            commit: Some("abc1234".into()),
+            is_dirty: false,
+            os: "linux",
+            arch: "x86_64",
+            cpus: 8,
+            cpu_model: Some("Mock CPU, 3GHz".into()),
+            rustc: Some("rustc 1.74.0".into()),
+            is_release: true,
+        }
+    }
+
+    fn get_mock_timings() -> Vec<Timings> {
+        vec![Timings {
+            day: day!(1),
+            parse: None,
+            part_1: Some(Measurement {
+                time: "10.0µs".into(),
+                nanos: 10000.0,
+                samples: 100,
+            }),
+            part_2: Some(Measurement {
+                time: "20.0µs".into(),
+                nanos: 20000.0,
+                samples: 50,
+            }),
+            parse_mem: None,
+            part_1_mem: Some("2 allocs, 64 B, 64 B peak".into()),
+            part_2_mem: None,
+            total_nanos: 30000.0,
+        }]
+    }
+
+    #[test]
+    fn parses_formats() {
+        assert_eq!("md".parse::<Format>().unwrap(), Format::Markdown);
+        assert_eq!("csv".parse::<Format>().unwrap(), Format::Csv);
+        assert_eq!("json".parse::<Format>().unwrap(), Format::Json);
+        assert!("xml".parse::<Format>().is_err());
+        assert_eq!(Format::from_path("bench.JSON"), Format::Json);
+        assert_eq!(Format::from_path("bench"), Format::Markdown);
+    }
+
+    #[test]
+    fn formats_markdown() {
+        let report = to_markdown(&get_mock_timings(), 0.03, &get_mock_environment());
+        assert!(report.contains("- **Commit:** `abc1234`"));
+        assert!(report.contains("- **Machine:** linux x86_64, 8 CPUs, Mock CPU, 3GHz"));
+        assert!(report.contains("- **Total:** 0.03ms"));
+        assert!(report.contains(
+            "| 1 | - | `10.0µs` @ 100 samples<br>`2 allocs, 64 B, 64 B peak` | `20.0µs` @ 50 samples | 0.03ms |"
+        ));
+    }
+
+    #[test]
+    fn formats_csv() {
+        let report = to_csv(&get_mock_timings(), 0.03, &get_mock_environment());
+        let lines: Vec<&str> = report.lines().collect();
+        assert_eq!(lines.len(), 4);
+        assert_eq!(
+            lines[1],
+            r#"1,part_1,10.0µs,10000,100,"2 allocs, 64 B, 64 B peak",abc1234,"linux x86_64, 8 CPUs, Mock CPU, 3GHz",release"#
+        );
+        assert!(lines[3].starts_with("total,,0.03ms,30000,,,abc1234,"));
+    }
+
+    #[test]
+    fn formats_json() {
+        let report = to_json(&get_mock_timings(), 0.03, &get_mock_environment());
+        assert!(report.contains(r#""commit": "abc1234","#));
+        assert!(report.contains(r#""parse": null"#));
+        assert!(report.contains(
+            r#""part_2": {"time": "20.0µs", "nanos": 20000, "samples": 50, "memory": null}"#
+        ));
+        assert_eq!(json_string("a\"b\\c\n"), r#""a\"b\\c\n""#);
+    }
+}
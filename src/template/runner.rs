@@ -1,8 +1,11 @@
 /// Encapsulates code that interacts with solution functions.
-use crate::template::{aoc_cli, ANSI_ITALIC, ANSI_RESET};
+use crate::template::memory::{self, MemoryStats};
+use crate::template::{aoc_cli, profiler, ANSI_ITALIC, ANSI_RESET};
 use crate::Day;
 use std::fmt::Display;
+use std::fs;
 use std::io::{stdout, Write};
+use std::path::Path;
 use std::process::Output;
 use std::time::{Duration, Instant};
 use std::{cmp, env, process};
@@ -12,16 +15,74 @@ use super::ANSI_BOLD;
 pub fn run_part<I: Clone, T: Display>(func: impl Fn(I) -> Option<T>, input: I, day: Day, part: u8) {
     let part_str = format!("Part {part}");
 
-    let (result, duration, samples) =
-        run_timed(func, input, |result| print_result(result, &part_str, ""));
+    let (result, duration, samples) = run_timed(&func, input.clone(), |result| {
+        print_result(result, &part_str, "");
+    });
 
-    print_result(&result, &part_str, &format_duration(&duration, samples));
+    let stats_str = format_stats(&func, input.clone(), &duration, samples);
+
+    print_result(&result, &part_str, &stats_str);
+
+    if env::args().any(|x| x == "--profile") {
+        let name = if part == 1 { "part_one" } else { "part_two" };
+        write_profile(|| func(input.clone()), name, day);
+    }
 
     if let Some(result) = result {
         submit_result(result, day, part);
     }
 }
 
+/// Run the optional parse step of a solution. The parsed value is returned so it can be shared by both parts.
+/// If the input cannot be parsed, the error is printed and the process exits.
+pub fn run_parse<I: Clone, P, E: Display>(
+    func: impl Fn(I) -> Result<P, E>,
+    input: I,
+    day: Day,
+) -> P {
+    let (parsed, duration, samples) = run_timed(&func, input.clone(), |parsed| {
+        print!("Parse: {}", if parsed.is_ok() { "✔" } else { "✖" });
+    });
+
+    let parsed = match parsed {
+        Ok(parsed) => parsed,
+        Err(err) => {
+            print!("\r");
+            println!("Parse: ✖             ");
+            eprintln!("Failed to parse the input of day {day}: {err}");
+            process::exit(1);
+        }
+    };
+
+    let stats_str = format_stats(&func, input.clone(), &duration, samples);
+
+    print!("\r");
+    println!("Parse: ✔{stats_str}");
+
+    if env::args().any(|x| x == "--profile") {
+        write_profile(|| func(input.clone()), "parse", day);
+    }
+
+    parsed
+}
+
+/// Formats the timing of a run. In `--mem` mode, the function is executed once more to collect allocation stats.
+fn format_stats<I, T>(
+    func: impl Fn(I) -> T,
+    input: I,
+    duration: &Duration,
+    samples: u128,
+) -> String {
+    let mut stats_str = format_duration(duration, samples);
+
+    if env::args().any(|x| x == "--mem") {
+        let (_, stats) = memory::measure(|| func(input));
+        stats_str.push_str(&format_memory(&stats));
+    }
+
+    stats_str
+}
+
 /// Run a solution part. The behavior differs depending on whether we are running a release or debug build:
 ///  1. in debug, the function is executed once.
 ///  2. in release, the function is benched (approx. 1 second of execution time or 10 samples, whatever take longer.)
@@ -51,13 +112,8 @@ fn bench<I: Clone, T>(func: impl Fn(I) -> T, input: I, base_time: &Duration) ->
     print!(" > {ANSI_ITALIC}benching{ANSI_RESET}");
     let _ = stdout.flush();
 
-    let bench_iterations = cmp::min(
-        10000,
-        cmp::max(
-            Duration::from_secs(1).as_nanos() / cmp::max(base_time.as_nanos(), 10),
-            10,
-        ),
-    );
+    let bench_iterations =
+        (Duration::from_secs(1).as_nanos() / cmp::max(base_time.as_nanos(), 10)).clamp(10, 10000);
 
     let mut timers: Vec<Duration> = vec![];
 
@@ -92,6 +148,41 @@ fn format_duration(duration: &Duration, samples: u128) -> String {
     }
 }
 
+/// Records a profile of `func` and writes it to `target/aoc-profile` as folded stacks and flamegraph SVG.
+fn write_profile<T>(func: impl FnMut() -> T, name: &'static str, day: Day) {
+    print!(" > {ANSI_ITALIC}profiling {name}{ANSI_RESET}");
+    let _ = stdout().flush();
+
+    let profile = profiler::record(name, func, Duration::from_secs(1));
+    let dir = Path::new("target").join("aoc-profile");
+    let folded_path = dir.join(format!("{day}-{name}.folded"));
+    let svg_path = dir.join(format!("{day}-{name}.svg"));
+    let title = format!("Day {day} {name} ({} iterations)", profile.iterations);
+
+    let written = fs::create_dir_all(&dir)
+        .and_then(|()| fs::write(&folded_path, profile.to_folded()))
+        .and_then(|()| fs::write(&svg_path, profile.to_svg(&title)));
+
+    print!("\r");
+    match written {
+        Ok(()) => println!(
+            "Profile: {} ({} iterations)",
+            svg_path.display(),
+            profile.iterations
+        ),
+        Err(e) => eprintln!("Failed to write profile for {name}: {e}"),
+    }
+}
+
+fn format_memory(stats: &MemoryStats) -> String {
+    format!(
+        " [mem: {} allocs, {}, {} peak]",
+        stats.allocations,
+        memory::format_bytes(stats.bytes_allocated),
+        memory::format_bytes(stats.peak_heap)
+    )
+}
+
 fn print_result<T: Display>(result: &Option<T>, part: &str, duration_str: &str) {
     let is_intermediate_result = duration_str.is_empty();
 
@@ -0,0 +1,78 @@
+//! Helpers for the tests of the library and of the solutions
+//!
+//! Property tests compare an implementation against a slow but obvious one on random cases.
+//! [`Rng`] creates those cases without a dependency, and a fixed seed makes every run check the same cases.
+//! The module does not need the `test_lib` feature, since the tests of the solutions link the library without it.
+//!
+//! ```
+//! use advent_of_code::testing::Rng;
+//!
+//! let mut rng = Rng::new(42);
+//! let roll = rng.range(1, 6);
+//! assert!((1..=6).contains(&roll));
+//! assert!(rng.below(10) < 10);
+//! ```
+
+/// A xorshift generator, good enough to create random test cases.
+#[derive(Clone, Debug)]
+pub struct Rng(u64);
+
+impl Rng {
+    /// A generator that starts at `seed`. Xorshift gets stuck at zero, so a zero seed is replaced.
+    pub fn new(seed: u64) -> Self {
+        Rng(if seed == 0 {
+            0x9e37_79b9_7f4a_7c15
+        } else {
+            seed
+        })
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        self.0
+    }
+
+    /// A number in `0..n`, `n` must not be zero.
+    pub fn below(&mut self, n: u64) -> u64 {
+        self.next_u64() % n
+    }
+
+    /// A number in `low..=high`
+    pub fn range(&mut self, low: i64, high: i64) -> i64 {
+        let width = high.abs_diff(low).wrapping_add(1);
+        if width == 0 {
+            // the range covers every `i64`.
+            return self.next_u64() as i64;
+        }
+        low.wrapping_add(self.below(width) as i64)
+    }
+
+    /// One of `items`, which must not be empty.
+    pub fn pick<T: Copy>(&mut self, items: &[T]) -> T {
+        items[self.below(items.len() as u64) as usize]
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::Rng;
+
+    #[test]
+    fn stays_in_bounds() {
+        let mut rng = Rng::new(0);
+        for _ in 0..1000 {
+            assert!((-3..=3).contains(&rng.range(-3, 3)));
+            assert!(rng.below(7) < 7);
+            assert!(b"ab".contains(&rng.pick(b"ab")));
+        }
+        assert_eq!(rng.range(5, 5), 5);
+        let _ = rng.range(i64::MIN, i64::MAX);
+
+        let (mut a, mut b) = (Rng::new(1), Rng::new(1));
+        assert!((0..10).all(|_| a.next_u64() == b.next_u64()));
+    }
+}